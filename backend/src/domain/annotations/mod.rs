@@ -13,6 +13,7 @@ use uuid::Uuid;
 use super::UserId;
 
 mod query_impl;
+pub mod retry_worker;
 pub mod service;
 mod service_ops;
 #[cfg(test)]
@@ -0,0 +1,144 @@
+//! Durable retry worker for annotation mutations that fail transiently.
+//!
+//! When a job queue is attached,
+//! [`RouteAnnotationsService`](super::service::RouteAnnotationsService) enqueues
+//! a job here when a note upsert, note delete or progress update carrying an
+//! idempotency key fails with a retryable
+//! ([`ErrorCode::ServiceUnavailable`]) error. Requests without an idempotency
+//! key and batch requests are not enqueued.
+//! [`RouteAnnotationsRetryWorker`] claims queued jobs and replays them through
+//! the same [`RouteAnnotationsCommand`] port the original request used, so the
+//! existing idempotency machinery makes the replay safe even if the original
+//! mutation actually succeeded before the failure was observed.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::ports::{
+    ClaimedJob, DeleteNoteRequest, JobQueueError, JobQueueRepository, RouteAnnotationsCommand,
+    UpdateProgressRequest, UpsertNoteRequest,
+};
+use crate::domain::{Error, ErrorCode};
+
+/// Name of the job queue used for deferred annotation mutation retries.
+pub const ANNOTATION_RETRY_QUEUE: &str = "route-annotations-retry";
+
+/// A deferred annotation mutation, persisted as a job queue payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(super) enum AnnotationRetryJob {
+    UpsertNote(UpsertNoteRequest),
+    DeleteNote(DeleteNoteRequest),
+    UpdateProgress(UpdateProgressRequest),
+}
+
+/// Worker that retries annotation mutations from the durable job queue.
+///
+/// `run_once` claims at most one job per call; callers are expected to drive
+/// it from a polling loop.
+pub struct RouteAnnotationsRetryWorker {
+    queue: Arc<dyn JobQueueRepository>,
+    command: Arc<dyn RouteAnnotationsCommand>,
+    worker_id: String,
+}
+
+impl RouteAnnotationsRetryWorker {
+    /// Create a worker claiming jobs under `worker_id`.
+    pub fn new(
+        queue: Arc<dyn JobQueueRepository>,
+        command: Arc<dyn RouteAnnotationsCommand>,
+        worker_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            queue,
+            command,
+            worker_id: worker_id.into(),
+        }
+    }
+
+    /// Claim and process a single job, if one is available.
+    ///
+    /// Returns `Ok(false)` if the queue was empty, and `Ok(true)` if a job was
+    /// claimed and its retry either succeeded (the job is completed) or failed
+    /// with a retryable error (the job stays `running` until reaped). Any
+    /// other retry failure is returned as `Err`, and that job is not
+    /// completed either.
+    pub async fn run_once(&self) -> Result<bool, Error> {
+        let Some(job) = self
+            .queue
+            .claim(ANNOTATION_RETRY_QUEUE, &self.worker_id)
+            .await
+            .map_err(map_job_queue_error)?
+        else {
+            return Ok(false);
+        };
+
+        match self.retry_job(&job).await {
+            Ok(()) => {
+                self.queue
+                    .complete(job.id)
+                    .await
+                    .map_err(map_job_queue_error)?;
+            }
+            Err(error) if error.code() == ErrorCode::ServiceUnavailable => {
+                // Leave the row as `running`; the reaper releases it back to
+                // `new` once its heartbeat lapses so another attempt is made.
+            }
+            Err(error) => return Err(error),
+        }
+
+        Ok(true)
+    }
+
+    async fn retry_job(&self, job: &ClaimedJob) -> Result<(), Error> {
+        let retry_job: AnnotationRetryJob = serde_json::from_value(job.payload.clone())
+            .map_err(|err| Error::internal(format!("malformed annotation retry payload: {err}")))?;
+
+        match retry_job {
+            AnnotationRetryJob::UpsertNote(request) => {
+                self.command.upsert_note(request).await?;
+            }
+            AnnotationRetryJob::DeleteNote(request) => {
+                self.command.delete_note(request).await?;
+            }
+            AnnotationRetryJob::UpdateProgress(request) => {
+                self.command.update_progress(request).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn map_job_queue_error(error: JobQueueError) -> Error {
+    match error {
+        JobQueueError::Connection { message } => {
+            Error::service_unavailable(format!("job queue unavailable: {message}"))
+        }
+        JobQueueError::Query { message } => Error::internal(format!("job queue error: {message}")),
+    }
+}
+
+/// Release `running` jobs whose heartbeat is older than `lease_timeout` back
+/// to `new`, so a crashed worker's jobs are retried by someone else.
+///
+/// [`JobQueueRepository::release_stale`] is not scoped to a queue, so this
+/// releases stale jobs on every queue, not only [`ANNOTATION_RETRY_QUEUE`].
+pub async fn reap_stale_jobs(
+    queue: &dyn JobQueueRepository,
+    lease_timeout: Duration,
+) -> Result<u64, Error> {
+    let older_than = Utc::now()
+        - chrono::Duration::from_std(lease_timeout)
+            .map_err(|err| Error::internal(format!("invalid lease timeout: {err}")))?;
+    queue
+        .release_stale(older_than)
+        .await
+        .map_err(map_job_queue_error)
+}
+
+#[cfg(test)]
+#[path = "retry_worker_tests.rs"]
+mod retry_worker_tests;
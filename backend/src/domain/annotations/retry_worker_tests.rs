@@ -0,0 +1,161 @@
+//! Tests for the route annotations retry worker.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::{AnnotationRetryJob, RouteAnnotationsRetryWorker, reap_stale_jobs};
+use crate::domain::ports::{
+    ClaimedJob, JobQueueError, JobQueueRepository, MockRouteAnnotationsCommand, UpsertNoteRequest,
+    UpsertNoteResponse,
+};
+use crate::domain::{Error, RouteNote, UserId};
+
+/// Hand-rolled [`JobQueueRepository`] stub.
+///
+/// `JobQueueRepository` has no `mockall::automock` attribute, unlike
+/// [`MockRouteAnnotationsCommand`], so the queue side of these tests is
+/// exercised with a small in-memory fake instead.
+#[derive(Default)]
+struct StubJobQueueRepository {
+    job: Mutex<Option<ClaimedJob>>,
+    completed: Mutex<Vec<i64>>,
+    released: AtomicU64,
+}
+
+impl StubJobQueueRepository {
+    fn with_job(job: ClaimedJob) -> Self {
+        Self {
+            job: Mutex::new(Some(job)),
+            completed: Mutex::new(Vec::new()),
+            released: AtomicU64::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl JobQueueRepository for StubJobQueueRepository {
+    async fn enqueue(
+        &self,
+        _queue: &str,
+        _payload: serde_json::Value,
+    ) -> Result<(), JobQueueError> {
+        Ok(())
+    }
+
+    async fn claim(
+        &self,
+        _queue: &str,
+        _worker_id: &str,
+    ) -> Result<Option<ClaimedJob>, JobQueueError> {
+        Ok(self.job.lock().expect("lock").take())
+    }
+
+    async fn heartbeat(&self, _job_id: i64) -> Result<(), JobQueueError> {
+        Ok(())
+    }
+
+    async fn complete(&self, job_id: i64) -> Result<(), JobQueueError> {
+        self.completed.lock().expect("lock").push(job_id);
+        Ok(())
+    }
+
+    async fn release_stale(&self, _older_than: DateTime<Utc>) -> Result<u64, JobQueueError> {
+        Ok(self.released.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+}
+
+fn upsert_job(id: i64) -> ClaimedJob {
+    let request = UpsertNoteRequest {
+        note_id: Uuid::new_v4(),
+        route_id: Uuid::new_v4(),
+        poi_id: None,
+        user_id: UserId::random(),
+        body: "retried note".to_owned(),
+        expected_revision: None,
+        idempotency_key: None,
+    };
+    let payload = serde_json::to_value(AnnotationRetryJob::UpsertNote(request))
+        .expect("job serializes");
+    ClaimedJob {
+        id,
+        queue: super::ANNOTATION_RETRY_QUEUE.to_owned(),
+        payload,
+    }
+}
+
+#[tokio::test]
+async fn run_once_reports_empty_queue() {
+    let queue = Arc::new(StubJobQueueRepository::default());
+    let command = Arc::new(MockRouteAnnotationsCommand::new());
+    let worker = RouteAnnotationsRetryWorker::new(queue, command, "worker-1");
+
+    let claimed = worker.run_once().await.expect("run_once succeeds");
+    assert!(!claimed);
+}
+
+#[tokio::test]
+async fn run_once_replays_job_and_completes_it_on_success() {
+    let queue = Arc::new(StubJobQueueRepository::with_job(upsert_job(42)));
+    let mut command = MockRouteAnnotationsCommand::new();
+    command.expect_upsert_note().times(1).returning(|request| {
+        Ok(UpsertNoteResponse {
+            note: RouteNote::new(
+                request.note_id,
+                request.route_id,
+                request.user_id,
+                crate::domain::RouteNoteContent::new(request.body),
+            ),
+            replayed: false,
+        })
+    });
+    let worker = RouteAnnotationsRetryWorker::new(queue.clone(), Arc::new(command), "worker-1");
+
+    let claimed = worker.run_once().await.expect("run_once succeeds");
+    assert!(claimed);
+    assert_eq!(*queue.completed.lock().expect("lock"), vec![42]);
+}
+
+#[tokio::test]
+async fn run_once_leaves_job_in_place_on_retryable_failure() {
+    let queue = Arc::new(StubJobQueueRepository::with_job(upsert_job(7)));
+    let mut command = MockRouteAnnotationsCommand::new();
+    command
+        .expect_upsert_note()
+        .times(1)
+        .returning(|_| Err(Error::service_unavailable("annotation repository unavailable")));
+    let worker = RouteAnnotationsRetryWorker::new(queue.clone(), Arc::new(command), "worker-1");
+
+    let claimed = worker.run_once().await.expect("run_once succeeds");
+    assert!(claimed);
+    assert!(queue.completed.lock().expect("lock").is_empty());
+}
+
+#[tokio::test]
+async fn run_once_surfaces_non_retryable_failure() {
+    let queue = Arc::new(StubJobQueueRepository::with_job(upsert_job(9)));
+    let mut command = MockRouteAnnotationsCommand::new();
+    command
+        .expect_upsert_note()
+        .times(1)
+        .returning(|_| Err(Error::conflict("revision mismatch")));
+    let worker = RouteAnnotationsRetryWorker::new(queue.clone(), Arc::new(command), "worker-1");
+
+    let result = worker.run_once().await;
+    assert!(result.is_err());
+    assert!(queue.completed.lock().expect("lock").is_empty());
+}
+
+#[tokio::test]
+async fn reap_stale_jobs_delegates_to_release_stale() {
+    let queue = StubJobQueueRepository::default();
+
+    let released = reap_stale_jobs(&queue, Duration::from_secs(30))
+        .await
+        .expect("reap succeeds");
+    assert_eq!(released, 1);
+}
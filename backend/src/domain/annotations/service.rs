@@ -7,34 +7,130 @@ use std::future::Future;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::json;
+use tokio::sync::broadcast;
 
 use crate::domain::ports::{
-    DeleteNoteRequest, DeleteNoteResponse, IdempotencyRepository, IdempotencyRepositoryError,
-    RouteAnnotationRepository, RouteAnnotationRepositoryError, RouteAnnotationsCommand,
-    UpdateProgressRequest, UpdateProgressResponse, UpsertNoteRequest, UpsertNoteResponse,
+    ApplyBatchRequest, ApplyBatchResponse, DeleteNoteRequest, DeleteNoteResponse,
+    IdempotencyRepository, IdempotencyRepositoryError, JobQueueRepository,
+    NoOpRouteAnnotationsTelemetry, RouteAnnotationRepository, RouteAnnotationRepositoryError,
+    RouteAnnotationsCommand, RouteAnnotationsTelemetry, UpdateProgressRequest,
+    UpdateProgressResponse, UpsertNoteRequest, UpsertNoteResponse,
 };
 use crate::domain::{
-    Error, IdempotencyKey, IdempotencyLookupQuery, IdempotencyLookupResult, IdempotencyRecord,
-    MutationType, PayloadHash, UserId, canonicalize_and_hash,
+    Error, ErrorCode, IdempotencyKey, IdempotencyLookupQuery, IdempotencyLookupResult,
+    IdempotencyRecord, MutationType, PayloadHash, UserId, canonicalize_and_hash,
 };
 
+use super::retry_worker::{ANNOTATION_RETRY_QUEUE, AnnotationRetryJob};
+
+/// Key identifying a single logical mutation for in-process coalescing.
+type SingleFlightKey = (IdempotencyKey, UserId, MutationType, PayloadHash);
+
+/// Outcome broadcast to waiters coalesced onto an in-flight mutation.
+///
+/// Responses are carried as their serialized snapshot because the map is
+/// shared across the different response types produced by
+/// [`RouteAnnotationsCommand`]; each waiter deserializes into its own
+/// concrete response type before marking it replayed.
+type SingleFlightOutcome = Result<serde_json::Value, Error>;
+
+/// Capacity of the per-mutation broadcast channel.
+///
+/// Only the single outcome is ever sent before the channel is torn down, so
+/// a capacity of one is sufficient.
+const SINGLE_FLIGHT_CHANNEL_CAPACITY: usize = 1;
+
+/// RAII guard that owns the in-flight slot for a single-flight mutation.
+///
+/// Dropping the guard without calling [`Self::finish`] (because `operation()`
+/// panicked or the leader's future was dropped mid-flight) still clears the map
+/// slot and wakes any waiters with an error, so a crashed leader can never
+/// strand concurrent callers.
+struct SingleFlightGuard {
+    in_flight: Arc<DashMap<SingleFlightKey, broadcast::Sender<SingleFlightOutcome>>>,
+    key: SingleFlightKey,
+    sender: broadcast::Sender<SingleFlightOutcome>,
+    finished: bool,
+}
+
+impl SingleFlightGuard {
+    fn new(
+        in_flight: Arc<DashMap<SingleFlightKey, broadcast::Sender<SingleFlightOutcome>>>,
+        key: SingleFlightKey,
+        sender: broadcast::Sender<SingleFlightOutcome>,
+    ) -> Self {
+        Self {
+            in_flight,
+            key,
+            sender,
+            finished: false,
+        }
+    }
+
+    /// Publish the outcome to waiters and release the in-flight slot.
+    fn finish(mut self, outcome: SingleFlightOutcome) {
+        self.finished = true;
+        self.in_flight.remove(&self.key);
+        let _ = self.sender.send(outcome);
+    }
+}
+
+impl Drop for SingleFlightGuard {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.in_flight.remove(&self.key);
+        let _ = self.sender.send(Err(Error::internal(
+            "single-flight leader exited before producing a result",
+        )));
+    }
+}
+
 /// Route annotations service implementing the driving ports.
 #[derive(Clone)]
 pub struct RouteAnnotationsService<R, I> {
     pub(super) annotations_repo: Arc<R>,
     idempotency_repo: Arc<I>,
+    in_flight: Arc<DashMap<SingleFlightKey, broadcast::Sender<SingleFlightOutcome>>>,
+    job_queue: Option<Arc<dyn JobQueueRepository>>,
+    telemetry: Arc<dyn RouteAnnotationsTelemetry>,
 }
 
 impl<R, I> RouteAnnotationsService<R, I> {
     /// Create a new service with the given repositories.
+    ///
+    /// Telemetry defaults to [`NoOpRouteAnnotationsTelemetry`]; attach a real
+    /// recorder with [`Self::with_telemetry`].
     pub fn new(annotations_repo: Arc<R>, idempotency_repo: Arc<I>) -> Self {
         Self {
             annotations_repo,
             idempotency_repo,
+            in_flight: Arc::new(DashMap::new()),
+            job_queue: None,
+            telemetry: Arc::new(NoOpRouteAnnotationsTelemetry),
         }
     }
+
+    /// Attach a durable job queue used to retry mutations that fail with a
+    /// retryable ([`ErrorCode::ServiceUnavailable`]) error.
+    #[must_use]
+    pub fn with_job_queue(mut self, job_queue: Arc<dyn JobQueueRepository>) -> Self {
+        self.job_queue = Some(job_queue);
+        self
+    }
+
+    /// Attach a telemetry recorder for idempotency/concurrency lifecycle
+    /// events. Defaults to a no-op recorder.
+    #[must_use]
+    pub fn with_telemetry(mut self, telemetry: Arc<dyn RouteAnnotationsTelemetry>) -> Self {
+        self.telemetry = telemetry;
+        self
+    }
 }
 
 impl<R, I> RouteAnnotationsService<R, I>
@@ -105,6 +201,42 @@ where
         response
     }
 
+    /// Enqueue `job` for a later retry if `error` is retryable and a job
+    /// queue is configured.
+    ///
+    /// Enqueue failures are swallowed: the caller already has the original
+    /// error to surface, and losing the retry is no worse than the
+    /// pre-existing behaviour of requiring the client to retry manually.
+    async fn enqueue_retry(&self, error: &Error, job: AnnotationRetryJob) {
+        if error.code() != ErrorCode::ServiceUnavailable {
+            return;
+        }
+        let Some(queue) = &self.job_queue else {
+            return;
+        };
+        let Ok(payload) = serde_json::to_value(&job) else {
+            return;
+        };
+        let _ = queue.enqueue(ANNOTATION_RETRY_QUEUE, payload).await;
+    }
+
+    /// Record a telemetry event for a revision mismatch if `error` is one.
+    ///
+    /// [`Self::map_annotations_error`] tags revision-mismatch conflicts with
+    /// `details.code == "revision_mismatch"`; other conflicts (e.g. an
+    /// idempotency key reused with a different payload) are recorded
+    /// elsewhere, where the branch that produces them is already known.
+    async fn record_revision_mismatch_telemetry(&self, error: &Error, mutation_type: MutationType) {
+        let is_revision_mismatch = error
+            .details()
+            .and_then(|details| details.get("code"))
+            .and_then(|code| code.as_str())
+            == Some("revision_mismatch");
+        if is_revision_mismatch {
+            let _ = self.telemetry.record_revision_mismatch(mutation_type).await;
+        }
+    }
+
     fn note_payload_hash(request: &UpsertNoteRequest) -> PayloadHash {
         let payload = json!({
             "routeId": request.route_id,
@@ -132,10 +264,32 @@ where
         canonicalize_and_hash(&payload)
     }
 
+    fn batch_payload_hash(request: &ApplyBatchRequest) -> PayloadHash {
+        let payload = json!({
+            "operations": request.operations,
+        });
+        canonicalize_and_hash(&payload)
+    }
+
+    /// Attach `index` to `error`'s details, identifying which batch item
+    /// failed, preserving any details the error already carried.
+    pub(super) fn with_batch_index(error: Error, index: usize) -> Error {
+        let mut details = error.details().cloned().unwrap_or_else(|| json!({}));
+        if let Some(details) = details.as_object_mut() {
+            details.insert("index".to_owned(), json!(index));
+        }
+        error.with_details(details)
+    }
+
     async fn handle_duplicate_key_race<T>(&self, context: &IdempotencyContext) -> Result<T, Error>
     where
         T: DeserializeOwned + HasReplayFlag,
     {
+        let _ = self
+            .telemetry
+            .record_duplicate_race(context.mutation_type)
+            .await;
+
         let query = context.lookup_query();
         let retry_result = self
             .idempotency_repo
@@ -157,11 +311,73 @@ where
         }
     }
 
+    /// Await the outcome broadcast by the in-flight leader for this mutation.
+    async fn await_in_flight<T>(
+        mut receiver: broadcast::Receiver<SingleFlightOutcome>,
+    ) -> Result<T, Error>
+    where
+        T: DeserializeOwned + HasReplayFlag,
+    {
+        match receiver.recv().await {
+            Ok(Ok(snapshot)) => {
+                let response = Self::deserialize_response(snapshot)?;
+                Ok(Self::mark_replayed(response))
+            }
+            Ok(Err(err)) => Err(err),
+            Err(_closed_or_lagged) => Err(Error::internal(
+                "single-flight coordinator ended without a result",
+            )),
+        }
+    }
+
+    /// Coalesce concurrent callers sharing an idempotency key onto one
+    /// execution of `operation`.
+    ///
+    /// The first caller becomes the single-flight leader: it performs the
+    /// lookup/operation/store sequence and broadcasts the outcome to any
+    /// waiters before releasing the slot. Concurrent callers with an
+    /// identical key await that broadcast instead of re-running `operation`.
+    /// Callers whose payload hash differs use a different key and fall
+    /// through to the existing DB-level duplicate-key handling, which is
+    /// still required as the cross-process fallback.
     async fn handle_idempotent<T, F, Fut>(
         &self,
         context: IdempotencyContext,
         operation: F,
     ) -> Result<T, Error>
+    where
+        T: DeserializeOwned + Serialize + HasReplayFlag,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let key = context.single_flight_key();
+        let guard = match self.in_flight.entry(key.clone()) {
+            Entry::Occupied(entry) => {
+                let receiver = entry.get().subscribe();
+                drop(entry);
+                return Self::await_in_flight(receiver).await;
+            }
+            Entry::Vacant(entry) => {
+                let (sender, _receiver) = broadcast::channel(SINGLE_FLIGHT_CHANNEL_CAPACITY);
+                entry.insert(sender.clone());
+                SingleFlightGuard::new(Arc::clone(&self.in_flight), key, sender)
+            }
+        };
+
+        let result = self.execute_idempotent(&context, operation).await;
+        let outcome = match &result {
+            Ok(response) => Self::serialize_response(response),
+            Err(err) => Err(err.clone()),
+        };
+        guard.finish(outcome);
+        result
+    }
+
+    async fn execute_idempotent<T, F, Fut>(
+        &self,
+        context: &IdempotencyContext,
+        operation: F,
+    ) -> Result<T, Error>
     where
         T: DeserializeOwned + Serialize + HasReplayFlag,
         F: FnOnce() -> Fut,
@@ -181,20 +397,27 @@ where
                 let record = context.record(response_snapshot);
 
                 match self.idempotency_repo.store(&record).await {
-                    Ok(()) => Ok(response),
+                    Ok(()) => {
+                        let _ = self.telemetry.record_fresh(context.mutation_type).await;
+                        Ok(response)
+                    }
                     Err(IdempotencyRepositoryError::DuplicateKey { .. }) => {
-                        self.handle_duplicate_key_race(&context).await
+                        self.handle_duplicate_key_race(context).await
                     }
                     Err(err) => Err(Self::map_idempotency_error(err)),
                 }
             }
             IdempotencyLookupResult::MatchingPayload(record) => {
+                let _ = self.telemetry.record_replay(context.mutation_type).await;
                 let response = Self::deserialize_response(record.response_snapshot)?;
                 Ok(Self::mark_replayed(response))
             }
-            IdempotencyLookupResult::ConflictingPayload(_) => Err(Error::conflict(
-                "idempotency key already used with different payload",
-            )),
+            IdempotencyLookupResult::ConflictingPayload(_) => {
+                let _ = self.telemetry.record_conflict(context.mutation_type).await;
+                Err(Error::conflict(
+                    "idempotency key already used with different payload",
+                ))
+            }
         }
     }
 }
@@ -222,6 +445,15 @@ impl IdempotencyContext {
         }
     }
 
+    fn single_flight_key(&self) -> SingleFlightKey {
+        (
+            self.key.clone(),
+            self.user_id.clone(),
+            self.mutation_type,
+            self.payload_hash.clone(),
+        )
+    }
+
     fn lookup_query(&self) -> IdempotencyLookupQuery {
         IdempotencyLookupQuery::new(
             self.key.clone(),
@@ -265,14 +497,23 @@ where
             MutationType::Notes,
             payload_hash,
         );
-        self.handle_idempotent(context, || async {
-            let note = self.perform_upsert_note(&request).await?;
-            Ok(UpsertNoteResponse {
-                note,
-                replayed: false,
+        let result = self
+            .handle_idempotent(context, || async {
+                let note = self.perform_upsert_note(&request).await?;
+                Ok(UpsertNoteResponse {
+                    note,
+                    replayed: false,
+                })
             })
-        })
-        .await
+            .await;
+
+        if let Err(error) = &result {
+            self.record_revision_mismatch_telemetry(error, MutationType::Notes)
+                .await;
+            self.enqueue_retry(error, AnnotationRetryJob::UpsertNote(request))
+                .await;
+        }
+        result
     }
 
     async fn delete_note(&self, request: DeleteNoteRequest) -> Result<DeleteNoteResponse, Error> {
@@ -291,14 +532,21 @@ where
             MutationType::Notes,
             payload_hash,
         );
-        self.handle_idempotent(context, || async {
-            let deleted = self.perform_delete_note(&request).await?;
-            Ok(DeleteNoteResponse {
-                deleted,
-                replayed: false,
+        let result = self
+            .handle_idempotent(context, || async {
+                let deleted = self.perform_delete_note(&request).await?;
+                Ok(DeleteNoteResponse {
+                    deleted,
+                    replayed: false,
+                })
             })
-        })
-        .await
+            .await;
+
+        if let Err(error) = &result {
+            self.enqueue_retry(error, AnnotationRetryJob::DeleteNote(request))
+                .await;
+        }
+        result
     }
 
     async fn update_progress(
@@ -320,14 +568,49 @@ where
             MutationType::Progress,
             payload_hash,
         );
-        self.handle_idempotent(context, || async {
-            let progress = self.perform_update_progress(&request).await?;
-            Ok(UpdateProgressResponse {
-                progress,
-                replayed: false,
+        let result = self
+            .handle_idempotent(context, || async {
+                let progress = self.perform_update_progress(&request).await?;
+                Ok(UpdateProgressResponse {
+                    progress,
+                    replayed: false,
+                })
+            })
+            .await;
+
+        if let Err(error) = &result {
+            self.record_revision_mismatch_telemetry(error, MutationType::Progress)
+                .await;
+            self.enqueue_retry(error, AnnotationRetryJob::UpdateProgress(request))
+                .await;
+        }
+        result
+    }
+
+    async fn apply_batch(&self, request: ApplyBatchRequest) -> Result<ApplyBatchResponse, Error> {
+        let payload_hash = Self::batch_payload_hash(&request);
+        let context = IdempotencyContext::new(
+            request.idempotency_key.clone(),
+            request.user_id.clone(),
+            MutationType::AnnotationsBatch,
+            payload_hash,
+        );
+
+        let result = self
+            .handle_idempotent(context, || async {
+                let items = self.perform_apply_batch(&request.operations).await?;
+                Ok(ApplyBatchResponse {
+                    items,
+                    replayed: false,
+                })
             })
-        })
-        .await
+            .await;
+
+        if let Err(error) = &result {
+            self.record_revision_mismatch_telemetry(error, MutationType::AnnotationsBatch)
+                .await;
+        }
+        result
     }
 }
 
@@ -353,6 +636,12 @@ impl HasReplayFlag for DeleteNoteResponse {
     }
 }
 
+impl HasReplayFlag for ApplyBatchResponse {
+    fn mark_replayed(&mut self) {
+        self.replayed = true;
+    }
+}
+
 #[cfg(test)]
 #[path = "service_tests.rs"]
 mod service_tests;
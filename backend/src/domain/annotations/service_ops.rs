@@ -7,7 +7,10 @@ use crate::domain::ports::{IdempotencyRepository, RouteAnnotationRepository};
 use crate::domain::{Error, RouteNote, RouteNoteContent, RouteProgress};
 
 use super::service::RouteAnnotationsService;
-use crate::domain::ports::{DeleteNoteRequest, UpdateProgressRequest, UpsertNoteRequest};
+use crate::domain::ports::{
+    AnnotationBatchItemResult, AnnotationBatchOperation, AnnotationBatchWrite,
+    AnnotationBatchWriteOutcome, DeleteNoteRequest, UpdateProgressRequest, UpsertNoteRequest,
+};
 
 impl<R, I> RouteAnnotationsService<R, I>
 where
@@ -23,7 +26,24 @@ where
             .find_note_by_id(&request.note_id)
             .await
             .map_err(Self::map_annotations_error)?;
+        let note = Self::resolve_note(existing, request)?;
 
+        self.annotations_repo
+            .save_note(&note, request.expected_revision)
+            .await
+            .map_err(Self::map_annotations_error)?;
+
+        Ok(note)
+    }
+
+    /// Validate `request` against `existing` and build the note to persist,
+    /// without writing it. Shared by [`Self::perform_upsert_note`] (which
+    /// writes immediately) and [`Self::perform_apply_batch`] (which plans
+    /// all batch writes before committing any of them atomically).
+    fn resolve_note(
+        existing: Option<RouteNote>,
+        request: &UpsertNoteRequest,
+    ) -> Result<RouteNote, Error> {
         if let Some(note) = &existing {
             if note.user_id != request.user_id {
                 return Err(Error::forbidden("not authorised to update this note"));
@@ -33,8 +53,8 @@ where
             }
         }
 
-        let note = match (existing, request.expected_revision) {
-            (None, None) => RouteNote::new(
+        match (existing, request.expected_revision) {
+            (None, None) => Ok(RouteNote::new(
                 request.note_id,
                 request.route_id,
                 request.user_id.clone(),
@@ -42,13 +62,9 @@ where
                     body: request.body.clone(),
                     poi_id: request.poi_id,
                 },
-            ),
-            (None, Some(expected)) => {
-                return Err(Self::revision_conflict(Some(expected), 0));
-            }
-            (Some(existing), None) => {
-                return Err(Self::revision_conflict(None, existing.revision));
-            }
+            )),
+            (None, Some(expected)) => Err(Self::revision_conflict(Some(expected), 0)),
+            (Some(existing), None) => Err(Self::revision_conflict(None, existing.revision)),
             (Some(existing), Some(expected)) => {
                 if existing.revision != expected {
                     return Err(Self::revision_conflict(Some(expected), existing.revision));
@@ -70,16 +86,9 @@ where
                     builder = builder.poi_id(poi_id);
                 }
 
-                builder.build()
+                Ok(builder.build())
             }
-        };
-
-        self.annotations_repo
-            .save_note(&note, request.expected_revision)
-            .await
-            .map_err(Self::map_annotations_error)?;
-
-        Ok(note)
+        }
     }
 
     pub(super) async fn perform_update_progress(
@@ -91,36 +100,43 @@ where
             .find_progress(&request.route_id, &request.user_id)
             .await
             .map_err(Self::map_annotations_error)?;
+        let progress = Self::resolve_progress(existing, request)?;
+
+        self.annotations_repo
+            .save_progress(&progress, request.expected_revision)
+            .await
+            .map_err(Self::map_annotations_error)?;
+
+        Ok(progress)
+    }
 
-        let progress = match (existing, request.expected_revision) {
-            (None, None) => RouteProgress::builder(request.route_id, request.user_id.clone())
+    /// Validate `request` against `existing` and build the progress to
+    /// persist, without writing it. Shared by
+    /// [`Self::perform_update_progress`] (which writes immediately) and
+    /// [`Self::perform_apply_batch`] (which plans all batch writes before
+    /// committing any of them atomically).
+    fn resolve_progress(
+        existing: Option<RouteProgress>,
+        request: &UpdateProgressRequest,
+    ) -> Result<RouteProgress, Error> {
+        match (existing, request.expected_revision) {
+            (None, None) => Ok(RouteProgress::builder(request.route_id, request.user_id.clone())
                 .visited_stop_ids(request.visited_stop_ids.clone())
                 .revision(1)
-                .build(),
-            (None, Some(expected)) => {
-                return Err(Self::revision_conflict(Some(expected), 0));
-            }
-            (Some(existing), None) => {
-                return Err(Self::revision_conflict(None, existing.revision));
-            }
+                .build()),
+            (None, Some(expected)) => Err(Self::revision_conflict(Some(expected), 0)),
+            (Some(existing), None) => Err(Self::revision_conflict(None, existing.revision)),
             (Some(existing), Some(expected)) => {
                 if existing.revision != expected {
                     return Err(Self::revision_conflict(Some(expected), existing.revision));
                 }
-                RouteProgress::builder(request.route_id, request.user_id.clone())
+                Ok(RouteProgress::builder(request.route_id, request.user_id.clone())
                     .visited_stop_ids(request.visited_stop_ids.clone())
                     .updated_at(chrono::Utc::now())
                     .revision(expected + 1)
-                    .build()
+                    .build())
             }
-        };
-
-        self.annotations_repo
-            .save_progress(&progress, request.expected_revision)
-            .await
-            .map_err(Self::map_annotations_error)?;
-
-        Ok(progress)
+        }
     }
 
     pub(super) async fn perform_delete_note(
@@ -145,4 +161,122 @@ where
             .await
             .map_err(Self::map_annotations_error)
     }
+
+    /// Apply each operation in `operations` atomically: plans every
+    /// operation in order (resolving authorisation and revision checks
+    /// against a pre-transaction read, exactly as the single-operation
+    /// methods do), then commits all of the resulting writes in one database
+    /// transaction. If any operation fails validation, or any write fails at
+    /// commit time (e.g. a revision bumped by a concurrent request), none of
+    /// the batch's writes are persisted.
+    ///
+    /// The failing operation's index is attached to the propagated error's
+    /// details under `index`, so callers can report which item in the batch
+    /// was rejected.
+    pub(super) async fn perform_apply_batch(
+        &self,
+        operations: &[AnnotationBatchOperation],
+    ) -> Result<Vec<AnnotationBatchItemResult>, Error> {
+        let mut writes = Vec::with_capacity(operations.len());
+        for (index, operation) in operations.iter().enumerate() {
+            let write = self
+                .plan_batch_write(operation)
+                .await
+                .map_err(|error| Self::with_batch_index(error, index))?;
+            writes.push(write);
+        }
+
+        if writes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let outcomes = self
+            .annotations_repo
+            .apply_batch(&writes)
+            .await
+            .map_err(|(index, error)| {
+                Self::with_batch_index(Self::map_annotations_error(error), index)
+            })?;
+
+        Ok(writes
+            .into_iter()
+            .zip(outcomes)
+            .map(Self::batch_item_result)
+            .collect())
+    }
+
+    /// Validate a single batch operation against freshly-read state and
+    /// build the write it requires, without persisting anything.
+    async fn plan_batch_write(
+        &self,
+        operation: &AnnotationBatchOperation,
+    ) -> Result<AnnotationBatchWrite, Error> {
+        match operation {
+            AnnotationBatchOperation::UpsertNote(request) => {
+                let existing = self
+                    .annotations_repo
+                    .find_note_by_id(&request.note_id)
+                    .await
+                    .map_err(Self::map_annotations_error)?;
+                let note = Self::resolve_note(existing, request)?;
+                Ok(AnnotationBatchWrite::UpsertNote {
+                    note,
+                    expected_revision: request.expected_revision,
+                })
+            }
+            AnnotationBatchOperation::DeleteNote(request) => {
+                if let Some(note) = self
+                    .annotations_repo
+                    .find_note_by_id(&request.note_id)
+                    .await
+                    .map_err(Self::map_annotations_error)?
+                    && note.user_id != request.user_id
+                {
+                    return Err(Error::forbidden("not authorised to delete this note"));
+                }
+                // Deleting a note that no longer exists is a harmless no-op;
+                // the write still executes so the batch commits as one
+                // transaction, and the outcome reports `deleted: false`.
+                Ok(AnnotationBatchWrite::DeleteNote {
+                    note_id: request.note_id,
+                })
+            }
+            AnnotationBatchOperation::UpdateProgress(request) => {
+                let existing = self
+                    .annotations_repo
+                    .find_progress(&request.route_id, &request.user_id)
+                    .await
+                    .map_err(Self::map_annotations_error)?;
+                let progress = Self::resolve_progress(existing, request)?;
+                Ok(AnnotationBatchWrite::UpdateProgress {
+                    progress,
+                    expected_revision: request.expected_revision,
+                })
+            }
+        }
+    }
+
+    /// Pair a planned write with its committed outcome to build the batch
+    /// item result the caller sees.
+    fn batch_item_result(
+        (write, outcome): (AnnotationBatchWrite, AnnotationBatchWriteOutcome),
+    ) -> AnnotationBatchItemResult {
+        match (write, outcome) {
+            (
+                AnnotationBatchWrite::UpsertNote { note, .. },
+                AnnotationBatchWriteOutcome::NoteUpserted,
+            ) => AnnotationBatchItemResult::UpsertNote(note),
+            (
+                AnnotationBatchWrite::DeleteNote { .. },
+                AnnotationBatchWriteOutcome::NoteDeleted(deleted),
+            ) => AnnotationBatchItemResult::DeleteNote { deleted },
+            (
+                AnnotationBatchWrite::UpdateProgress { progress, .. },
+                AnnotationBatchWriteOutcome::ProgressUpdated,
+            ) => AnnotationBatchItemResult::UpdateProgress(progress),
+            (write, outcome) => unreachable!(
+                "apply_batch outcome kind must match its write kind, got {write:?}/{outcome:?}"
+            ),
+        }
+    }
 }
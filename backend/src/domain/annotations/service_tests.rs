@@ -5,17 +5,19 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use super::PayloadHashable;
-use super::RouteAnnotationsService;
+use super::{IdempotencyContext, RouteAnnotationsService};
 use crate::domain::ports::{
-    FixtureIdempotencyRepository, MockIdempotencyRepository, MockRouteAnnotationRepository,
-    RouteAnnotationsCommand, UpdateProgressRequest, UpdateProgressResponse, UpsertNoteRequest,
-    UpsertNoteResponse,
+    AnnotationBatchItemResult, AnnotationBatchOperation, AnnotationBatchWriteOutcome,
+    ApplyBatchRequest, FixtureIdempotencyRepository, MockIdempotencyRepository,
+    MockRouteAnnotationRepository, MockRouteAnnotationsTelemetry, RouteAnnotationsCommand,
+    UpdateProgressRequest, UpdateProgressResponse, UpsertNoteRequest, UpsertNoteResponse,
 };
 use crate::domain::{
     Error, IdempotencyKey, IdempotencyLookupQuery, IdempotencyLookupResult, IdempotencyRecord,
     MutationType, RouteNote, RouteNoteContent, RouteProgress, UserId,
 };
 use chrono::Utc;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 fn make_service(
@@ -352,3 +354,358 @@ async fn update_progress_rejects_revision_mismatch() {
 async fn update_progress_replays_cached_response_for_same_idempotency_key() {
     ReplayCase::Progress.assert_replay().await;
 }
+
+#[tokio::test]
+async fn concurrent_callers_with_same_key_coalesce_onto_one_execution() {
+    let user_id = UserId::random();
+    let idempotency_key = IdempotencyKey::random();
+    let route_id = Uuid::new_v4();
+    let note_id = Uuid::new_v4();
+    let request = UpsertNoteRequest {
+        note_id,
+        route_id,
+        poi_id: None,
+        user_id: user_id.clone(),
+        body: "hello".to_owned(),
+        expected_revision: None,
+        idempotency_key: Some(idempotency_key.clone()),
+    };
+    let payload_hash = request.compute_payload_hash();
+
+    let mut repo = MockRouteAnnotationRepository::new();
+    repo.expect_find_note_by_id().times(0);
+    repo.expect_save_note().times(0);
+
+    let service = make_service_with_idempotency(repo, MockIdempotencyRepository::new());
+    let context = IdempotencyContext::new(idempotency_key, user_id, MutationType::Notes, payload_hash);
+    let key = context.single_flight_key();
+
+    let (sender, _) = broadcast::channel(1);
+    service.in_flight.insert(key, sender.clone());
+
+    let note = RouteNote::new(
+        note_id,
+        route_id,
+        request.user_id.clone(),
+        RouteNoteContent::new("hello"),
+    );
+    let snapshot = serde_json::to_value(UpsertNoteResponse {
+        note: note.clone(),
+        replayed: false,
+    })
+    .expect("response snapshot");
+
+    let publish_once_subscribed = tokio::spawn(async move {
+        while sender.receiver_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+        sender.send(Ok(snapshot)).expect("publish outcome");
+    });
+
+    let response: UpsertNoteResponse = service
+        .handle_idempotent(context, || async {
+            panic!("operation must not run for a coalesced caller")
+        })
+        .await
+        .expect("coalesced response");
+
+    publish_once_subscribed.await.expect("publisher task");
+
+    assert!(response.replayed);
+    assert_eq!(response.note.id, note_id);
+}
+
+#[tokio::test]
+async fn single_flight_slot_is_released_after_the_leader_finishes() {
+    let mut repo = MockRouteAnnotationRepository::new();
+    repo.expect_find_note_by_id()
+        .times(1)
+        .return_once(|_| Ok(None));
+    repo.expect_save_note().times(1).return_once(|_, _| Ok(()));
+
+    let mut idempotency_repo = MockIdempotencyRepository::new();
+    idempotency_repo
+        .expect_lookup()
+        .times(1)
+        .returning(|_| Ok(IdempotencyLookupResult::NotFound));
+    idempotency_repo
+        .expect_store()
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let service = make_service_with_idempotency(repo, idempotency_repo);
+    let request = UpsertNoteRequest {
+        note_id: Uuid::new_v4(),
+        route_id: Uuid::new_v4(),
+        poi_id: None,
+        user_id: UserId::random(),
+        body: "hello".to_owned(),
+        expected_revision: None,
+        idempotency_key: Some(IdempotencyKey::random()),
+    };
+
+    let response = service.upsert_note(request).await.expect("upsert ok");
+
+    assert!(!response.replayed);
+    assert!(
+        service.in_flight.is_empty(),
+        "the in-flight slot must be released once the leader finishes"
+    );
+}
+
+#[tokio::test]
+async fn apply_batch_applies_operations_in_order() {
+    let user_id = UserId::random();
+    let note_id = Uuid::new_v4();
+    let route_id = Uuid::new_v4();
+    let progress_route_id = Uuid::new_v4();
+    let mut repo = MockRouteAnnotationRepository::new();
+    repo.expect_find_note_by_id()
+        .times(1)
+        .return_once(|_| Ok(None));
+    repo.expect_find_progress()
+        .times(1)
+        .return_once(|_, _| Ok(None));
+    repo.expect_apply_batch().times(1).return_once(|writes| {
+        Ok(writes
+            .iter()
+            .map(|write| match write {
+                crate::domain::ports::AnnotationBatchWrite::UpsertNote { .. } => {
+                    AnnotationBatchWriteOutcome::NoteUpserted
+                }
+                crate::domain::ports::AnnotationBatchWrite::DeleteNote { .. } => {
+                    AnnotationBatchWriteOutcome::NoteDeleted(false)
+                }
+                crate::domain::ports::AnnotationBatchWrite::UpdateProgress { .. } => {
+                    AnnotationBatchWriteOutcome::ProgressUpdated
+                }
+            })
+            .collect())
+    });
+
+    let service = make_service(repo);
+    let request = ApplyBatchRequest {
+        user_id: user_id.clone(),
+        operations: vec![
+            AnnotationBatchOperation::UpsertNote(UpsertNoteRequest {
+                note_id,
+                route_id,
+                poi_id: None,
+                user_id: user_id.clone(),
+                body: "batched note".to_owned(),
+                expected_revision: None,
+                idempotency_key: None,
+            }),
+            AnnotationBatchOperation::UpdateProgress(UpdateProgressRequest {
+                route_id: progress_route_id,
+                user_id,
+                visited_stop_ids: vec![Uuid::new_v4()],
+                expected_revision: None,
+                idempotency_key: None,
+            }),
+        ],
+        idempotency_key: IdempotencyKey::random(),
+    };
+
+    let response = service.apply_batch(request).await.expect("batch ok");
+
+    assert!(!response.replayed);
+    assert_eq!(response.items.len(), 2);
+    assert!(matches!(
+        response.items[0],
+        AnnotationBatchItemResult::UpsertNote(_)
+    ));
+    assert!(matches!(
+        response.items[1],
+        AnnotationBatchItemResult::UpdateProgress(_)
+    ));
+}
+
+#[tokio::test]
+async fn apply_batch_aborts_and_reports_failing_index_on_revision_mismatch() {
+    let user_id = UserId::random();
+    let note_id = Uuid::new_v4();
+    let route_id = Uuid::new_v4();
+    let mut repo = MockRouteAnnotationRepository::new();
+    repo.expect_find_note_by_id()
+        .times(1)
+        .return_once(|_| Ok(None));
+    // The second operation fails its revision check during planning, so no
+    // writes are ever committed.
+    repo.expect_find_progress()
+        .times(1)
+        .return_once(|_, _| Ok(None));
+    repo.expect_apply_batch().times(0);
+
+    let service = make_service(repo);
+    let request = ApplyBatchRequest {
+        user_id: user_id.clone(),
+        operations: vec![
+            AnnotationBatchOperation::UpsertNote(UpsertNoteRequest {
+                note_id,
+                route_id,
+                poi_id: None,
+                user_id: user_id.clone(),
+                body: "batched note".to_owned(),
+                expected_revision: None,
+                idempotency_key: None,
+            }),
+            AnnotationBatchOperation::UpdateProgress(UpdateProgressRequest {
+                route_id,
+                user_id,
+                visited_stop_ids: vec![],
+                expected_revision: Some(7),
+                idempotency_key: None,
+            }),
+        ],
+        idempotency_key: IdempotencyKey::random(),
+    };
+
+    let error = service
+        .apply_batch(request)
+        .await
+        .expect_err("revision mismatch aborts the batch");
+
+    assert_eq!(error.code(), crate::domain::ErrorCode::Conflict);
+    let details = error.details().expect("conflict carries details");
+    assert_eq!(details["index"], 1);
+}
+
+#[tokio::test]
+async fn apply_batch_replays_cached_response_for_same_idempotency_key() {
+    let user_id = UserId::random();
+    let idempotency_key = IdempotencyKey::random();
+    let route_id = Uuid::new_v4();
+    let note_id = Uuid::new_v4();
+    let request = ApplyBatchRequest {
+        user_id: user_id.clone(),
+        operations: vec![AnnotationBatchOperation::UpsertNote(UpsertNoteRequest {
+            note_id,
+            route_id,
+            poi_id: None,
+            user_id: user_id.clone(),
+            body: "cached".to_owned(),
+            expected_revision: None,
+            idempotency_key: None,
+        })],
+        idempotency_key: idempotency_key.clone(),
+    };
+    let payload_hash =
+        RouteAnnotationsService::<MockRouteAnnotationRepository, MockIdempotencyRepository>::batch_payload_hash(
+            &request,
+        );
+    let note = RouteNote::new(note_id, route_id, user_id.clone(), RouteNoteContent::new("cached"));
+    let response = crate::domain::ports::ApplyBatchResponse {
+        items: vec![AnnotationBatchItemResult::UpsertNote(note)],
+        replayed: false,
+    };
+    let response_snapshot = serde_json::to_value(&response).expect("response snapshot");
+    let record = IdempotencyRecord {
+        key: idempotency_key.clone(),
+        mutation_type: MutationType::AnnotationsBatch,
+        payload_hash: payload_hash.clone(),
+        response_snapshot,
+        user_id: user_id.clone(),
+        created_at: Utc::now(),
+    };
+
+    let mut repo = MockRouteAnnotationRepository::new();
+    repo.expect_find_note_by_id().times(0);
+    repo.expect_save_note().times(0);
+
+    let mut idempotency_repo = MockIdempotencyRepository::new();
+    idempotency_repo
+        .expect_lookup()
+        .withf(move |query: &IdempotencyLookupQuery| {
+            query.key == idempotency_key
+                && query.user_id == user_id
+                && query.mutation_type == MutationType::AnnotationsBatch
+                && query.payload_hash == payload_hash
+        })
+        .times(1)
+        .return_once(move |_| Ok(IdempotencyLookupResult::MatchingPayload(record)));
+    idempotency_repo.expect_store().times(0);
+
+    let service = make_service_with_idempotency(repo, idempotency_repo);
+
+    let response = service.apply_batch(request).await.expect("cached response");
+
+    assert!(response.replayed);
+    assert_eq!(response.items.len(), 1);
+}
+
+#[tokio::test]
+async fn upsert_note_records_fresh_telemetry_on_first_execution() {
+    let mut repo = MockRouteAnnotationRepository::new();
+    repo.expect_find_note_by_id()
+        .times(1)
+        .return_once(|_| Ok(None));
+    repo.expect_save_note().times(1).return_once(|_, _| Ok(()));
+
+    let mut idempotency_repo = MockIdempotencyRepository::new();
+    idempotency_repo
+        .expect_lookup()
+        .times(1)
+        .returning(|_| Ok(IdempotencyLookupResult::NotFound));
+    idempotency_repo.expect_store().times(1).returning(|_| Ok(()));
+
+    let mut telemetry = MockRouteAnnotationsTelemetry::new();
+    telemetry
+        .expect_record_fresh()
+        .withf(|mutation_type| *mutation_type == MutationType::Notes)
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let service = RouteAnnotationsService::new(Arc::new(repo), Arc::new(idempotency_repo))
+        .with_telemetry(Arc::new(telemetry));
+    let request = UpsertNoteRequest {
+        note_id: Uuid::new_v4(),
+        route_id: Uuid::new_v4(),
+        poi_id: None,
+        user_id: UserId::random(),
+        body: "hello".to_owned(),
+        expected_revision: None,
+        idempotency_key: Some(IdempotencyKey::random()),
+    };
+
+    service.upsert_note(request).await.expect("upsert ok");
+}
+
+#[tokio::test]
+async fn upsert_note_records_revision_mismatch_telemetry() {
+    let note_id = Uuid::new_v4();
+    let route_id = Uuid::new_v4();
+    let user_id = UserId::random();
+    let existing = RouteNote::new(
+        note_id,
+        route_id,
+        user_id.clone(),
+        RouteNoteContent::new("note"),
+    );
+    let mut repo = MockRouteAnnotationRepository::new();
+    repo.expect_find_note_by_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(existing)));
+
+    let mut telemetry = MockRouteAnnotationsTelemetry::new();
+    telemetry
+        .expect_record_revision_mismatch()
+        .withf(|mutation_type| *mutation_type == MutationType::Notes)
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let service = RouteAnnotationsService::new(Arc::new(repo), Arc::new(FixtureIdempotencyRepository))
+        .with_telemetry(Arc::new(telemetry));
+    let request = UpsertNoteRequest {
+        note_id,
+        route_id,
+        poi_id: None,
+        user_id,
+        body: "updated".to_owned(),
+        expected_revision: Some(5),
+        idempotency_key: Some(IdempotencyKey::random()),
+    };
+
+    let error = service.upsert_note(request).await.expect_err("conflict");
+    assert_eq!(error.code(), crate::domain::ErrorCode::Conflict);
+}
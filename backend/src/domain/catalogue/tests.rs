@@ -236,6 +236,16 @@ fn trending_highlight_rejects_empty_delta(localizations: LocalizationMap) {
     ));
 }
 
+#[rstest]
+fn trending_highlight_resolves_subtitle_for_locale(localizations: LocalizationMap) {
+    let highlight =
+        TrendingRouteHighlight::new(Uuid::new_v4(), Uuid::new_v4(), "+12%", localizations)
+            .expect("valid trending route highlight");
+
+    assert_eq!(highlight.subtitle_for("en-GB").name, "Scenic route");
+    assert_eq!(highlight.subtitle_for("de-DE").name, "Scenic route");
+}
+
 #[rstest]
 fn community_pick_rejects_empty_curator_name() {
     let result = CommunityPick::new(community_pick_draft(None, None, "  "));
@@ -5,7 +5,7 @@ use uuid::Uuid;
 
 use super::CatalogueValidationError;
 use super::validation::validate_non_empty_field;
-use crate::domain::localization::LocalizationMap;
+use crate::domain::localization::{LocalizationMap, LocalizedStringSet};
 
 /// Input payload for [`TrendingRouteHighlight::new`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -57,6 +57,15 @@ impl TrendingRouteHighlight {
     pub fn subtitle_localizations(&self) -> &LocalizationMap {
         &self.subtitle_localizations
     }
+
+    /// Resolve the subtitle for the locale negotiated from an
+    /// `Accept-Language` header value.
+    ///
+    /// Delegates to [`LocalizationMap::resolve`]; see that method for the
+    /// RFC 4647 "lookup" matching rules and fallback behaviour.
+    pub fn subtitle_for(&self, accept_language: &str) -> &LocalizedStringSet {
+        self.subtitle_localizations.resolve(accept_language)
+    }
 }
 
 impl TryFrom<TrendingRouteHighlightDraft> for TrendingRouteHighlight {
@@ -17,6 +17,10 @@ pub enum ErrorCode {
     Forbidden,
     /// The requested resource does not exist.
     NotFound,
+    /// The request conflicts with the current state of the resource.
+    Conflict,
+    /// A dependency required to serve the request is temporarily unavailable.
+    ServiceUnavailable,
     /// An unexpected error occurred on the server.
     InternalError,
 }
@@ -212,6 +216,30 @@ impl Error {
         Self::new(ErrorCode::NotFound, message)
     }
 
+    /// Convenience constructor for [`ErrorCode::Conflict`].
+    ///
+    /// # Examples
+    /// ```
+    /// use backend::domain::Error;
+    ///
+    /// let err = Error::conflict("revision mismatch");
+    /// ```
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Conflict, message)
+    }
+
+    /// Convenience constructor for [`ErrorCode::ServiceUnavailable`].
+    ///
+    /// # Examples
+    /// ```
+    /// use backend::domain::Error;
+    ///
+    /// let err = Error::service_unavailable("database unreachable");
+    /// ```
+    pub fn service_unavailable(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ServiceUnavailable, message)
+    }
+
     /// Convenience constructor for [`ErrorCode::InternalError`].
     ///
     /// # Examples
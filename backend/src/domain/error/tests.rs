@@ -24,6 +24,18 @@ fn invalid_request_constructor_sets_code() {
     assert_eq!(err.code(), ErrorCode::InvalidRequest);
 }
 
+#[rstest]
+fn conflict_constructor_sets_code() {
+    let err = Error::conflict("revision mismatch");
+    assert_eq!(err.code(), ErrorCode::Conflict);
+}
+
+#[rstest]
+fn service_unavailable_constructor_sets_code() {
+    let err = Error::service_unavailable("database unreachable");
+    assert_eq!(err.code(), ErrorCode::ServiceUnavailable);
+}
+
 #[rstest]
 fn try_new_rejects_empty_messages() {
     let result = Error::try_new(ErrorCode::InvalidRequest, "   ");
@@ -1,20 +1,26 @@
 //! Example data seeding orchestration.
 //!
 //! Converts deterministic example-data registry outputs into domain users and
-//! preferences, then delegates persistence to the seeding repository port.
+//! preferences, then delegates persistence to the seeding repository ports.
+//! The seed run's lifecycle (claim, then finalize) is tracked separately
+//! from the generated users themselves: [`ExampleDataSeeder`] claims the run
+//! via [`ExampleDataRunsRepository::try_record_seed`] before generating
+//! anything, so a seed that turns out to already be recorded skips
+//! generation entirely, and only finalizes the run once persistence of the
+//! generated users succeeds.
 
 use std::sync::Arc;
 
-use chrono::Utc;
 use example_data::{
     ExampleUserSeed, GenerationError, RegistryError, SeedDefinition, SeedRegistry, UnitSystemSeed,
     generate_example_users,
 };
+use mockable::Clock;
 use thiserror::Error;
 
 use crate::domain::ports::{
-    ExampleDataSeedRepository, ExampleDataSeedRepositoryError, ExampleDataSeedRequest,
-    ExampleDataSeedUser, SeedingResult,
+    ExampleDataRunsError, ExampleDataRunsRepository, ExampleDataSeedRepository,
+    ExampleDataSeedRepositoryError, ExampleDataSeedRequest, ExampleDataSeedUser, SeedingResult,
 };
 use crate::domain::{
     DisplayName, UnitSystem, User, UserId, UserPreferencesBuilder, UserValidationError,
@@ -52,27 +58,41 @@ pub enum ExampleDataSeedingError {
     /// Persistence adapter failed while seeding.
     #[error("example data persistence error: {0}")]
     Persistence(#[from] ExampleDataSeedRepositoryError),
+    /// Persistence adapter failed while claiming or finalizing the run.
+    #[error("example data run tracking error: {0}")]
+    RunsPersistence(#[from] ExampleDataRunsError),
 }
 
 /// Service that orchestrates example data seeding.
 #[derive(Clone)]
-pub struct ExampleDataSeeder<R> {
+pub struct ExampleDataSeeder<R, Runs> {
     repository: Arc<R>,
+    runs: Arc<Runs>,
+    clock: Arc<dyn Clock>,
 }
 
-impl<R> ExampleDataSeeder<R> {
-    /// Create a new seeder with the given persistence adapter.
-    pub fn new(repository: Arc<R>) -> Self {
-        Self { repository }
+impl<R, Runs> ExampleDataSeeder<R, Runs> {
+    /// Create a new seeder with the given persistence adapters.
+    pub fn new(repository: Arc<R>, runs: Arc<Runs>, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            repository,
+            runs,
+            clock,
+        }
     }
 }
 
-impl<R> ExampleDataSeeder<R>
+impl<R, Runs> ExampleDataSeeder<R, Runs>
 where
     R: ExampleDataSeedRepository,
+    Runs: ExampleDataRunsRepository,
 {
     /// Apply example data for a named seed within the registry.
     ///
+    /// Claims the seed run before generating anything; a seed that is
+    /// already recorded short-circuits here without regenerating or
+    /// re-persisting users.
+    ///
     /// # Errors
     ///
     /// Returns [`ExampleDataSeedingError`] if registry lookup, generation,
@@ -93,11 +113,24 @@ where
         let seed_value_i64 = i64::try_from(seed_value)
             .map_err(|_| ExampleDataSeedingError::SeedOverflow { seed: seed_value })?;
 
+        let claim = self
+            .runs
+            .try_record_seed(&seed_key, user_count_i32, seed_value_i64)
+            .await?;
+
+        let SeedingResult::Applied = claim else {
+            return Ok(ExampleDataSeedOutcome {
+                seed_key,
+                user_count,
+                result: claim,
+            });
+        };
+
         let seed_def = SeedDefinition::new(seed_key.clone(), seed_value, user_count);
         let example_users = generate_example_users(registry, &seed_def)?;
         let mut users = Vec::with_capacity(example_users.len());
         for seed_user in example_users {
-            users.push(convert_seed_user(seed_user)?);
+            users.push(convert_seed_user(seed_user, self.clock.as_ref())?);
         }
 
         let request = ExampleDataSeedRequest {
@@ -106,18 +139,20 @@ where
             seed: seed_value_i64,
             users,
         };
-        let result = self.repository.seed_example_data(request).await?;
+        self.repository.seed_example_data(request).await?;
+        self.runs.finalize_seed(&seed_key).await?;
 
         Ok(ExampleDataSeedOutcome {
             seed_key,
             user_count,
-            result,
+            result: SeedingResult::Applied,
         })
     }
 }
 
 fn convert_seed_user(
     seed_user: ExampleUserSeed,
+    clock: &dyn Clock,
 ) -> Result<ExampleDataSeedUser, UserValidationError> {
     let user_id = UserId::from_uuid(seed_user.id);
     let display_name = DisplayName::new(seed_user.display_name)?;
@@ -127,7 +162,7 @@ fn convert_seed_user(
         .safety_toggle_ids(seed_user.safety_toggle_ids)
         .unit_system(map_unit_system(seed_user.unit_system))
         .revision(1)
-        .updated_at(Utc::now())
+        .updated_at(clock.utc())
         .build();
 
     Ok(ExampleDataSeedUser { user, preferences })
@@ -145,7 +180,8 @@ mod tests {
     //! Unit tests for example data seeding orchestration.
 
     use super::*;
-    use crate::domain::ports::MockExampleDataSeedRepository;
+    use crate::domain::ports::{MockExampleDataRunsRepository, MockExampleDataSeedRepository};
+    use mockable::DefaultClock;
     use rstest::rstest;
 
     const REGISTRY_JSON: &str = r#"{
@@ -159,9 +195,28 @@ mod tests {
         SeedRegistry::from_json(REGISTRY_JSON).expect("registry should parse")
     }
 
+    fn seeder(
+        repo: MockExampleDataSeedRepository,
+        runs: MockExampleDataRunsRepository,
+    ) -> ExampleDataSeeder<MockExampleDataSeedRepository, MockExampleDataRunsRepository> {
+        ExampleDataSeeder::new(Arc::new(repo), Arc::new(runs), Arc::new(DefaultClock))
+    }
+
     #[rstest]
     #[tokio::test]
     async fn seed_applies_for_new_seed() {
+        let mut runs = MockExampleDataRunsRepository::new();
+        runs.expect_try_record_seed()
+            .withf(|seed_key, user_count, seed| {
+                seed_key == "mossy-owl" && *user_count == 2 && *seed == 42
+            })
+            .times(1)
+            .return_once(|_, _, _| Ok(SeedingResult::Applied));
+        runs.expect_finalize_seed()
+            .withf(|seed_key| seed_key == "mossy-owl")
+            .times(1)
+            .return_once(|_| Ok(()));
+
         let mut repo = MockExampleDataSeedRepository::new();
         repo.expect_seed_example_data()
             .withf(|request| {
@@ -171,10 +226,9 @@ mod tests {
                     && request.users.len() == 2
             })
             .times(1)
-            .return_once(|_| Ok(SeedingResult::Applied));
+            .return_once(|_| Ok(()));
 
-        let seeder = ExampleDataSeeder::new(Arc::new(repo));
-        let outcome = seeder
+        let outcome = seeder(repo, runs)
             .seed_from_registry(&registry(), "mossy-owl", None)
             .await
             .expect("seed succeeds");
@@ -187,28 +241,42 @@ mod tests {
     #[rstest]
     #[tokio::test]
     async fn seed_skips_when_already_seeded() {
+        let mut runs = MockExampleDataRunsRepository::new();
+        runs.expect_try_record_seed().times(1).return_once(|_, _, _| {
+            Ok(SeedingResult::AlreadySeeded {
+                recorded_user_count: 2,
+                recorded_seed: 0,
+            })
+        });
+        runs.expect_finalize_seed().times(0);
+
         let mut repo = MockExampleDataSeedRepository::new();
-        repo.expect_seed_example_data()
-            .times(1)
-            .return_once(|_| Ok(SeedingResult::AlreadySeeded));
+        repo.expect_seed_example_data().times(0);
 
-        let seeder = ExampleDataSeeder::new(Arc::new(repo));
-        let outcome = seeder
+        let outcome = seeder(repo, runs)
             .seed_from_registry(&registry(), "mossy-owl", None)
             .await
             .expect("seed succeeds");
 
-        assert_eq!(outcome.result, SeedingResult::AlreadySeeded);
+        assert_eq!(
+            outcome.result,
+            SeedingResult::AlreadySeeded {
+                recorded_user_count: 2,
+                recorded_seed: 0,
+            }
+        );
     }
 
     #[rstest]
     #[tokio::test]
     async fn seed_rejects_unknown_seed() {
-        let seeder = ExampleDataSeeder::new(Arc::new(MockExampleDataSeedRepository::new()));
-        let error = seeder
-            .seed_from_registry(&registry(), "missing-seed", None)
-            .await
-            .expect_err("missing seed should error");
+        let error = seeder(
+            MockExampleDataSeedRepository::new(),
+            MockExampleDataRunsRepository::new(),
+        )
+        .seed_from_registry(&registry(), "missing-seed", None)
+        .await
+        .expect_err("missing seed should error");
 
         assert!(matches!(error, ExampleDataSeedingError::Registry(_)));
     }
@@ -216,12 +284,13 @@ mod tests {
     #[rstest]
     #[tokio::test]
     async fn user_count_overflow_is_rejected() {
+        let mut runs = MockExampleDataRunsRepository::new();
+        runs.expect_try_record_seed().times(0);
         let mut repo = MockExampleDataSeedRepository::new();
         repo.expect_seed_example_data().times(0);
 
-        let seeder = ExampleDataSeeder::new(Arc::new(repo));
         let overflow_count = (i32::MAX as usize) + 1;
-        let error = seeder
+        let error = seeder(repo, runs)
             .seed_from_registry(&registry(), "mossy-owl", Some(overflow_count))
             .await
             .expect_err("overflow should be rejected");
@@ -242,7 +311,7 @@ mod tests {
             unit_system: UnitSystemSeed::Metric,
         };
 
-        let result = convert_seed_user(seed_user);
+        let result = convert_seed_user(seed_user, &DefaultClock);
         assert!(matches!(
             result,
             Err(UserValidationError::DisplayNameTooShort { .. })
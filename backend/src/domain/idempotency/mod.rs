@@ -69,18 +69,24 @@ pub enum MutationType {
     Preferences,
     /// Offline bundle operations (`POST/DELETE /api/v1/offline/bundles`).
     Bundles,
+    /// Batched route annotation operations (`RouteAnnotationsCommand::apply_batch`).
+    AnnotationsBatch,
+    /// Walk session creation (`POST /api/v1/walk-sessions`).
+    WalkSessions,
 }
 
 impl MutationType {
     /// All mutation type variants.
     ///
     /// Useful for iteration, validation, and documentation.
-    pub const ALL: [MutationType; 5] = [
+    pub const ALL: [MutationType; 7] = [
         MutationType::Routes,
         MutationType::Notes,
         MutationType::Progress,
         MutationType::Preferences,
         MutationType::Bundles,
+        MutationType::AnnotationsBatch,
+        MutationType::WalkSessions,
     ];
 }
 
@@ -96,6 +102,8 @@ impl MutationType {
     /// assert_eq!(MutationType::Progress.as_str(), "progress");
     /// assert_eq!(MutationType::Preferences.as_str(), "preferences");
     /// assert_eq!(MutationType::Bundles.as_str(), "bundles");
+    /// assert_eq!(MutationType::AnnotationsBatch.as_str(), "annotations_batch");
+    /// assert_eq!(MutationType::WalkSessions.as_str(), "walk_sessions");
     /// ```
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -104,6 +112,8 @@ impl MutationType {
             Self::Progress => "progress",
             Self::Preferences => "preferences",
             Self::Bundles => "bundles",
+            Self::AnnotationsBatch => "annotations_batch",
+            Self::WalkSessions => "walk_sessions",
         }
     }
 }
@@ -484,6 +494,11 @@ impl fmt::Display for PayloadHash {
 /// 3. The result is serialized to compact JSON (no whitespace).
 /// 4. SHA-256 is computed on the resulting UTF-8 bytes.
 ///
+/// The canonical bytes are streamed directly into the hasher via
+/// [`write_canonical`] rather than materialised as an intermediate
+/// `Vec<u8>`, so hashing a large payload does not triple its peak memory
+/// (once for the cloned, re-ordered `Value`, once for the serialized bytes).
+///
 /// # Example
 ///
 /// ```
@@ -494,18 +509,84 @@ impl fmt::Display for PayloadHash {
 /// assert_eq!(canonicalize_and_hash(&a), canonicalize_and_hash(&b));
 /// ```
 pub fn canonicalize_and_hash(value: &serde_json::Value) -> PayloadHash {
-    let canonical = canonicalize(value);
+    let mut writer = HashWriter(Sha256::new());
     #[expect(
         clippy::unwrap_used,
-        reason = "serde_json::Value serialization to JSON bytes is infallible"
+        reason = "writing into a Sha256-backed Write impl cannot fail"
     )]
-    let json_bytes = serde_json::to_vec(&canonical).unwrap();
-    let hash = Sha256::digest(&json_bytes);
+    write_canonical(value, &mut writer).unwrap();
+    let hash = writer.0.finalize();
     PayloadHash::from_bytes(&hash)
 }
 
+/// `std::io::Write` adapter that feeds written bytes straight into a
+/// [`Sha256`] hasher instead of buffering them.
+struct HashWriter(Sha256);
+
+impl std::io::Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Serialize `value` as compact JSON with object keys sorted lexicographically,
+/// writing directly to `writer` rather than building an intermediate `Value`.
+///
+/// Produces byte-identical output to `serde_json::to_vec(&canonicalize(value))`,
+/// but without cloning the tree first.
+fn write_canonical<W: std::io::Write>(
+    value: &serde_json::Value,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<(&String, &serde_json::Value)> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+            writer.write_all(b"{")?;
+            for (index, (key, entry_value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    writer.write_all(b",")?;
+                }
+                write_json_scalar(&serde_json::Value::String(key.clone()), writer)?;
+                writer.write_all(b":")?;
+                write_canonical(entry_value, writer)?;
+            }
+            writer.write_all(b"}")
+        }
+        serde_json::Value::Array(items) => {
+            writer.write_all(b"[")?;
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    writer.write_all(b",")?;
+                }
+                write_canonical(item, writer)?;
+            }
+            writer.write_all(b"]")
+        }
+        scalar => write_json_scalar(scalar, writer),
+    }
+}
+
+/// Delegate scalar (and string-escaping) serialization to `serde_json`.
+fn write_json_scalar<W: std::io::Write>(
+    value: &serde_json::Value,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    serde_json::to_writer(writer, value).map_err(std::io::Error::other)
+}
+
 /// Recursively sort object keys for canonical JSON representation.
-fn canonicalize(value: &serde_json::Value) -> serde_json::Value {
+///
+/// Retained for callers that want the reordered [`serde_json::Value`]
+/// itself; [`canonicalize_and_hash`] no longer uses this on its hot path,
+/// instead streaming bytes via [`write_canonical`] directly into the hasher.
+pub fn canonicalize(value: &serde_json::Value) -> serde_json::Value {
     match value {
         serde_json::Value::Object(map) => {
             let mut sorted: Vec<_> = map.iter().collect();
@@ -121,6 +121,23 @@ fn canonicalize_and_hash_handles_primitives() {
     );
 }
 
+#[test]
+fn canonicalize_and_hash_matches_hashing_the_canonicalized_value() {
+    let value = json!({"z": 1, "nested": {"b": 2, "a": [3, 1, 2]}, "a": "text"});
+    let canonical_bytes =
+        serde_json::to_vec(&canonicalize(&value)).expect("canonical value serializes");
+    let expected = PayloadHash::from_bytes(&Sha256::digest(&canonical_bytes));
+
+    assert_eq!(canonicalize_and_hash(&value), expected);
+}
+
+#[test]
+fn canonicalize_preserves_semantic_equality_when_reordering_keys() {
+    let value = json!({"z": 1, "a": 2});
+    let canonical = canonicalize(&value);
+    assert_eq!(canonical, json!({"a": 2, "z": 1}));
+}
+
 // MutationType tests
 
 #[rstest]
@@ -203,10 +220,17 @@ fn mutation_type_values_match_migration_constraint() {
 
     // These values must match the CHECK constraint in the migration file:
     // backend/migrations/2025-12-28-000000_add_mutation_type_to_idempotency_keys/up.sql
-    let migration_values: HashSet<&str> =
-        ["routes", "notes", "progress", "preferences", "bundles"]
-            .into_iter()
-            .collect();
+    let migration_values: HashSet<&str> = [
+        "routes",
+        "notes",
+        "progress",
+        "preferences",
+        "bundles",
+        "annotations_batch",
+        "walk_sessions",
+    ]
+    .into_iter()
+    .collect();
 
     let code_values: HashSet<&str> = MutationType::ALL.iter().map(|m| m.as_str()).collect();
 
@@ -146,6 +146,104 @@ impl LocalizationMap {
     pub fn as_map(&self) -> &BTreeMap<LocaleCode, LocalizedStringSet> {
         &self.0
     }
+
+    /// Resolve the best-matching locale for an `Accept-Language` header value.
+    ///
+    /// Implements the RFC 4647 §3.4 "lookup" algorithm: ranges are parsed
+    /// with their `q` weight, sorted by descending weight (ties keep the
+    /// header's original order, and `q=0` ranges are excluded), then each
+    /// range is matched against map keys case-insensitively, progressively
+    /// truncating trailing subtags (dropping a trailing singleton along with
+    /// it) until a key matches or only the primary subtag remains. Falls
+    /// back to the map's first locale when no range matches.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// use backend::domain::{LocalizationMap, LocalizedStringSet};
+    ///
+    /// let mut values = BTreeMap::new();
+    /// values.insert(
+    ///     "en-GB".to_owned(),
+    ///     LocalizedStringSet::new("Scenic route", None, None),
+    /// );
+    /// let map = LocalizationMap::new(values).expect("valid localization map");
+    ///
+    /// assert_eq!(map.resolve("en-US,en;q=0.5").name, "Scenic route");
+    /// ```
+    pub fn resolve(&self, accept_language: &str) -> &LocalizedStringSet {
+        for range in parse_accept_language(accept_language) {
+            if let Some(value) = self.lookup_range(&range) {
+                return value;
+            }
+        }
+
+        self.0
+            .values()
+            .next()
+            .expect("LocalizationMap is never empty")
+    }
+
+    fn lookup_range(&self, range: &str) -> Option<&LocalizedStringSet> {
+        let mut candidate = range.to_owned();
+        loop {
+            if let Some(value) = self
+                .0
+                .iter()
+                .find(|(locale, _)| locale.eq_ignore_ascii_case(&candidate))
+                .map(|(_, value)| value)
+            {
+                return Some(value);
+            }
+            candidate = truncate_subtag(&candidate)?;
+        }
+    }
+}
+
+/// Parse an `Accept-Language` header into language ranges ordered by
+/// descending `q` weight, excluding `q=0` ranges and the `*` wildcard (which
+/// names no specific locale to look up).
+fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut ranges: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|part| {
+            let range = part.split(';').next()?.trim();
+            if range.is_empty() || range == "*" {
+                return None;
+            }
+
+            let q = part
+                .split(';')
+                .skip(1)
+                .find_map(|param| param.trim().strip_prefix("q=")?.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            Some((range.to_owned(), q))
+        })
+        .filter(|(_, q)| *q > 0.0)
+        .collect();
+
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.into_iter().map(|(range, _)| range).collect()
+}
+
+/// Drop the trailing subtag from a language range, along with a trailing
+/// singleton (an extension/private-use marker such as `-x` or `-u`) left
+/// dangling by the removal, per RFC 4647 §3.4 step 2.
+fn truncate_subtag(range: &str) -> Option<String> {
+    let mut parts: Vec<&str> = range.split('-').collect();
+    if parts.len() <= 1 {
+        return None;
+    }
+
+    parts.pop();
+    while parts.len() > 1 && parts.last().is_some_and(|subtag| subtag.len() == 1) {
+        parts.pop();
+    }
+
+    Some(parts.join("-"))
 }
 
 impl TryFrom<BTreeMap<LocaleCode, LocalizedStringSet>> for LocalizationMap {
@@ -227,4 +325,65 @@ mod tests {
             .expect_err("invalid locale should fail deserialization");
         assert!(err.to_string().contains("must not be empty or padded"));
     }
+
+    fn sample_map() -> LocalizationMap {
+        let mut values = BTreeMap::new();
+        values.insert(
+            "en-GB".to_owned(),
+            LocalizedStringSet::new("Scenic route", None, None),
+        );
+        values.insert(
+            "fr-FR".to_owned(),
+            LocalizedStringSet::new("Itinéraire pittoresque", None, None),
+        );
+        LocalizationMap::new(values).expect("valid localization map")
+    }
+
+    #[rstest]
+    #[case::exact_match("fr-FR", "Itinéraire pittoresque")]
+    #[case::case_insensitive("FR-fr", "Itinéraire pittoresque")]
+    #[case::truncates_region_subtag("en-US", "Scenic route")]
+    #[case::prefers_higher_q("fr;q=0.2, en-GB;q=0.8", "Scenic route")]
+    #[case::ties_keep_source_order("fr-FR, en-GB", "Itinéraire pittoresque")]
+    #[case::excludes_zero_weight("fr-FR;q=0, en-GB", "Scenic route")]
+    #[case::empty_header("", "Scenic route")]
+    #[case::wildcard_falls_back_to_default("*", "Scenic route")]
+    #[case::unmatched_range_falls_back_to_default("de-DE", "Scenic route")]
+    fn resolve_selects_expected_locale(#[case] accept_language: &str, #[case] expected: &str) {
+        let map = sample_map();
+        assert_eq!(map.resolve(accept_language).name, expected);
+    }
+
+    /// Map whose default (first, alphabetically-lowest key) entry differs
+    /// from the entry a genuine truncation match should land on, so a
+    /// resolution that accidentally took the fallback path instead of
+    /// actually truncating would be caught.
+    fn truncation_sample_map() -> LocalizationMap {
+        let mut values = BTreeMap::new();
+        values.insert(
+            "de-DE".to_owned(),
+            LocalizedStringSet::new("Deutsch", None, None),
+        );
+        values.insert(
+            "en".to_owned(),
+            LocalizedStringSet::new("English", None, None),
+        );
+        values.insert(
+            "fr-FR".to_owned(),
+            LocalizedStringSet::new("Français", None, None),
+        );
+        LocalizationMap::new(values).expect("valid localization map")
+    }
+
+    #[rstest]
+    fn resolve_matches_via_genuine_progressive_truncation() {
+        let map = truncation_sample_map();
+
+        // "en-US-POSIX" matches no key outright. `truncate_subtag` must run
+        // twice (dropping "POSIX", then the non-singleton "US") before
+        // landing on "en", which is neither the requested range nor the
+        // map's default ("de-DE") — so this only passes if progressive
+        // truncation actually walks down to "en".
+        assert_eq!(map.resolve("en-US-POSIX").name, "English");
+    }
 }
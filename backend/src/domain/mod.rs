@@ -22,7 +22,12 @@
 //! - PayloadHash — SHA-256 hash of canonicalized request payload.
 //! - IdempotencyRecord — stored record for idempotency tracking.
 //! - IdempotencyLookupResult — outcome of idempotency key lookup.
+//! - RouteAnnotationsService — notes/progress command+query service, backed by
+//!   a repository, an idempotency store, and an optional durable retry queue.
+//! - WalkPrimaryStatDraft/WalkSecondaryStatDraft — validated input for the
+//!   stats recorded against a completed walk session.
 
+pub mod annotations;
 pub mod auth;
 pub mod error;
 pub mod idempotency;
@@ -34,16 +39,18 @@ pub mod user;
 pub mod user_events;
 pub mod user_interests;
 pub mod user_onboarding;
+pub mod walks;
 
+pub use self::annotations::service::RouteAnnotationsService;
+pub use self::annotations::{
+    RouteAnnotations, RouteNote, RouteNoteBuilder, RouteNoteContent, RouteProgress,
+    RouteProgressBuilder,
+};
 pub use self::auth::{LoginCredentials, LoginValidationError};
 pub use self::error::{Error, ErrorCode, ErrorValidationError};
 pub use self::idempotency::{
     IdempotencyKey, IdempotencyKeyValidationError, IdempotencyLookupResult, IdempotencyRecord,
-    PayloadHash, PayloadHashError, canonicalize_and_hash,
-};
-pub use self::idempotency::{
-    canonicalize_and_hash, IdempotencyKey, IdempotencyKeyValidationError, IdempotencyLookupResult,
-    IdempotencyRecord, PayloadHash, PayloadHashError,
+    MutationType, PayloadHash, PayloadHashError, canonicalize_and_hash,
 };
 pub use self::interest_theme::{InterestThemeId, InterestThemeIdValidationError};
 pub use self::route_submission::RouteSubmissionServiceImpl;
@@ -52,6 +59,9 @@ pub use self::user::{DisplayName, User, UserId, UserValidationError};
 pub use self::user_events::{DisplayNameRejectedEvent, UserCreatedEvent, UserEvent};
 pub use self::user_interests::UserInterests;
 pub use self::user_onboarding::UserOnboardingService;
+pub use self::walks::{
+    WalkPrimaryStatDraft, WalkPrimaryStatKind, WalkSecondaryStatDraft, WalkSecondaryStatKind,
+};
 
 /// HTTP header name used to propagate trace identifiers.
 pub const TRACE_ID_HEADER: &str = "trace-id";
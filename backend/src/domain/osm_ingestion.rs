@@ -75,9 +75,38 @@ impl GeofenceBounds {
     }
 }
 
+/// Digest algorithm tag carried by an [`InputDigest`].
+///
+/// Only `sha256` is supported today; the tag exists so a future algorithm can
+/// be added without changing the [`InputDigest`] wire format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Sha256,
+}
+
+impl DigestAlgorithm {
+    pub(super) fn parse(tag: &str) -> Option<Self> {
+        match tag {
+            "sha256" => Some(Self::Sha256),
+            _ => None,
+        }
+    }
+
+    /// The algorithm's canonical lowercase tag, as used in `sha256-<hex>` form.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+        }
+    }
+}
+
 /// Validated SHA-256 input digest.
+///
+/// Accepts either a bare 64-character hex digest (defaulting to `sha256`) or
+/// a tagged `sha256-<hex>` form, mirroring subresource-integrity metadata.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InputDigest {
+    algorithm: DigestAlgorithm,
     digest: String,
 }
 
@@ -89,15 +118,11 @@ impl InputDigest {
     /// assert_eq!(digest.as_str().len(), 64); // SHA-256 hex digest length.
     /// ```
     pub fn new(digest: String) -> Result<Self, Error> {
-        if !validation::is_valid_digest(&digest) {
-            return Err(Error::invalid_request(
-                "inputDigest must be a 64-character lowercase hexadecimal SHA-256 digest",
-            ));
-        }
-        Ok(Self { digest })
+        let (algorithm, digest) = validation::split_digest_algorithm(&digest)?;
+        Ok(Self { algorithm, digest })
     }
 
-    /// Borrow the underlying digest string.
+    /// Borrow the underlying hex digest string (without the algorithm tag).
     /// ```
     /// use backend::domain::osm_ingestion::InputDigest;
     /// let digest = InputDigest::new("a".repeat(64)).expect("valid digest");
@@ -106,6 +131,11 @@ impl InputDigest {
     pub fn as_str(&self) -> &str {
         &self.digest
     }
+
+    /// The digest algorithm this value was tagged with (or defaulted to).
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm
+    }
 }
 
 /// Validated geofence identifier.
@@ -154,9 +184,9 @@ impl SourceUrl {
         if trimmed.is_empty() {
             return Err(Error::invalid_request("sourceUrl must not be empty"));
         }
-        if Url::parse(trimmed).is_err() {
-            return Err(Error::invalid_request("sourceUrl must be a valid URL"));
-        }
+        let parsed = Url::parse(trimmed)
+            .map_err(|_| Error::invalid_request("sourceUrl must be a valid absolute URL"))?;
+        validation::validate_source_url(&parsed)?;
         Ok(Self(trimmed.to_owned()))
     }
 
@@ -278,35 +308,24 @@ where
     async fn ingest(&self, request: OsmIngestionRequest) -> Result<OsmIngestionOutcome, Error> {
         let validated_request = validate_request(&request)?;
 
-        if let Some(existing) = self.check_for_existing_rerun(&validated_request).await? {
+        if let Some(existing) = self.lookup_rerun(&validated_request).await? {
             return Ok(mapping::to_outcome(OsmIngestionStatus::Replayed, existing));
         }
 
-        let (filtered_records, raw_poi_count, filtered_poi_count) = self
-            .ingest_and_filter_pois(&request, &validated_request)
+        self.verify_source_digest(&request.osm_pbf_path, &validated_request.input_digest)
             .await?;
 
-        let provenance = OsmIngestionProvenanceRecord {
-            geofence_id: validated_request.geofence_id.as_str().to_owned(),
-            source_url: validated_request.source_url.as_str().to_owned(),
-            input_digest: validated_request.input_digest.as_str().to_owned(),
-            imported_at: self.clock.utc(),
-            geofence_bounds: validated_request.geofence_bounds.as_array(),
-            raw_poi_count,
-            filtered_poi_count,
-        };
-
-        if let Some(existing) = self
-            .persist_with_conflict_handling(&provenance, &filtered_records, &validated_request)
-            .await?
-        {
-            return Ok(mapping::to_outcome(OsmIngestionStatus::Replayed, existing));
-        }
+        let (source_report, raw_poi_count) = self.load_source(&request.osm_pbf_path).await?;
+        let (filtered_records, filtered_poi_count) =
+            self.filter_to_poi_records(source_report, &validated_request.geofence_bounds)?;
+
+        let provenance = self.build_provenance(&validated_request, raw_poi_count, filtered_poi_count);
+
+        let (status, provenance) = self
+            .persist_or_replay(provenance, &filtered_records, &validated_request)
+            .await?;
 
-        Ok(mapping::to_outcome(
-            OsmIngestionStatus::Executed,
-            provenance,
-        ))
+        Ok(mapping::to_outcome(status, provenance))
     }
 }
 
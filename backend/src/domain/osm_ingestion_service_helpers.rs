@@ -1,10 +1,16 @@
 //! Internal service helpers for OSM ingestion orchestration sequencing.
 
+use std::io;
 use std::path::Path;
 
+use cap_std::{ambient_authority, fs::Dir};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tokio::task;
+
 use super::{
-    Coordinate, GeofenceBounds, OsmIngestionCommandService, ValidatedOsmIngestionRequest, mapping,
-    to_poi_record,
+    Coordinate, DigestAlgorithm, GeofenceBounds, InputDigest, OsmIngestionCommandService,
+    ValidatedOsmIngestionRequest, mapping, to_poi_record,
 };
 use crate::domain::Error;
 use crate::domain::ports::{
@@ -18,6 +24,47 @@ where
     S: OsmSourceRepository,
     R: OsmIngestionProvenanceRepository,
 {
+    /// Verify that the bytes at `osm_pbf_path` hash to `expected`, making
+    /// deterministic reruns genuinely content-addressed rather than
+    /// caller-asserted.
+    pub(super) async fn verify_source_digest(
+        &self,
+        osm_pbf_path: &Path,
+        expected: &InputDigest,
+    ) -> Result<(), Error> {
+        let DigestAlgorithm::Sha256 = expected.algorithm();
+
+        let path = osm_pbf_path.to_path_buf();
+        let computed = task::spawn_blocking(move || compute_sha256_hex(&path))
+            .await
+            .map_err(|error| {
+                Error::internal(format!("failed to join digest verification task: {error}"))
+            })?
+            .map_err(|error| {
+                Error::internal(format!(
+                    "failed to read osm source for digest verification: {error}"
+                ))
+            })?;
+
+        let expected_hex = expected.as_str();
+        if computed.len() == expected_hex.len()
+            && bool::from(computed.as_bytes().ct_eq(expected_hex.as_bytes()))
+        {
+            return Ok(());
+        }
+
+        Err(
+            Error::invalid_request(format!(
+                "inputDigest mismatch: expected {expected_hex} but computed {computed} for {}",
+                osm_pbf_path.display()
+            ))
+            .with_details(serde_json::json!({
+                "expectedDigest": expected_hex,
+                "computedDigest": computed,
+            })),
+        )
+    }
+
     pub(super) async fn lookup_rerun(
         &self,
         validated_request: &ValidatedOsmIngestionRequest,
@@ -106,3 +153,34 @@ where
         }
     }
 }
+
+/// Stream `path` through a SHA-256 hasher without buffering the whole file,
+/// returning its hex-encoded digest.
+fn compute_sha256_hex(path: &Path) -> io::Result<String> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "input path must be a file"))?;
+    let directory = Dir::open_ambient_dir(parent, ambient_authority()).map_err(|error| {
+        io::Error::other(format!(
+            "open input parent directory '{}': {error}",
+            parent.display()
+        ))
+    })?;
+    let mut file = directory.open(Path::new(file_name)).map_err(|error| {
+        io::Error::other(format!("open input file '{}': {error}", path.display()))
+    })?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = [0_u8; 8 * 1024];
+    loop {
+        let read = io::Read::read(&mut file, &mut buffer).map_err(|error| {
+            io::Error::other(format!("read input file '{}': {error}", path.display()))
+        })?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -0,0 +1,76 @@
+//! Unit coverage for fetched-source subresource-integrity verification.
+
+use std::io::Write;
+
+use rstest::rstest;
+use sha2::{Digest, Sha256};
+use tempfile::NamedTempFile;
+
+use super::*;
+use crate::domain::ErrorCode;
+use crate::domain::osm_ingestion::InputDigest;
+use crate::domain::ports::{MockOsmIngestionProvenanceRepository, MockOsmSourceRepository};
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+fn service(
+) -> OsmIngestionCommandService<MockOsmSourceRepository, MockOsmIngestionProvenanceRepository> {
+    make_service(
+        MockOsmSourceRepository::new(),
+        MockOsmIngestionProvenanceRepository::new(),
+        fixture_clock(),
+    )
+}
+
+#[rstest]
+#[tokio::test]
+async fn verify_source_digest_accepts_matching_bytes() {
+    let mut file = NamedTempFile::new().expect("temp file");
+    file.write_all(b"wildside osm fixture").expect("write fixture");
+    let digest = InputDigest::new(sha256_hex(b"wildside osm fixture")).expect("valid digest");
+
+    service()
+        .verify_source_digest(file.path(), &digest)
+        .await
+        .expect("digest should match");
+}
+
+#[rstest]
+#[tokio::test]
+async fn verify_source_digest_accepts_tagged_sha256_form() {
+    let mut file = NamedTempFile::new().expect("temp file");
+    file.write_all(b"tagged digest fixture")
+        .expect("write fixture");
+    let digest = InputDigest::new(format!("sha256-{}", sha256_hex(b"tagged digest fixture")))
+        .expect("valid tagged digest");
+
+    service()
+        .verify_source_digest(file.path(), &digest)
+        .await
+        .expect("tagged digest should match");
+}
+
+#[rstest]
+#[tokio::test]
+async fn verify_source_digest_rejects_mismatched_bytes() {
+    let mut file = NamedTempFile::new().expect("temp file");
+    file.write_all(b"tampered bytes").expect("write fixture");
+    let digest = InputDigest::new(sha256_hex(b"wildside osm fixture")).expect("valid digest");
+
+    let error = service()
+        .verify_source_digest(file.path(), &digest)
+        .await
+        .expect_err("digest mismatch should fail");
+
+    assert_eq!(error.code(), ErrorCode::InvalidRequest);
+    assert!(error.message().contains("inputDigest mismatch"));
+    let details = error.details().expect("mismatch details present");
+    assert_eq!(
+        details.get("computedDigest").and_then(|v| v.as_str()),
+        Some(sha256_hex(b"tampered bytes").as_str())
+    );
+}
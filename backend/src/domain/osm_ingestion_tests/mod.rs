@@ -80,5 +80,6 @@ pub(super) fn make_service(
 }
 
 mod decode_element_id;
+mod digest_verification;
 mod ingest_behaviour;
 mod request_validation;
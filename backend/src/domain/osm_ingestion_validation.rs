@@ -1,5 +1,8 @@
 //! Internal validation helpers for OSM ingestion value-object construction.
 
+use url::Url;
+
+use super::DigestAlgorithm;
 use crate::domain::Error;
 
 pub(super) fn is_valid_digest(digest: &str) -> bool {
@@ -9,6 +12,58 @@ pub(super) fn is_valid_digest(digest: &str) -> bool {
             .all(|byte| byte.is_ascii_digit() || matches!(byte, b'a'..=b'f'))
 }
 
+/// Split an `InputDigest` source string into its algorithm tag and hex digest.
+///
+/// Accepts the bare 64-character hex form (defaulting to `sha256`, for
+/// backward compatibility with existing callers) or an explicitly tagged
+/// `sha256-<hex>` form mirroring subresource-integrity metadata strings.
+pub(super) fn split_digest_algorithm(raw: &str) -> Result<(DigestAlgorithm, String), Error> {
+    let Some((algorithm_tag, hex)) = raw.split_once('-') else {
+        if !is_valid_digest(raw) {
+            return Err(Error::invalid_request(
+                "inputDigest must be a 64-character lowercase hexadecimal SHA-256 digest",
+            ));
+        }
+        return Ok((DigestAlgorithm::Sha256, raw.to_owned()));
+    };
+
+    let algorithm = DigestAlgorithm::parse(algorithm_tag).ok_or_else(|| {
+        Error::invalid_request(format!("inputDigest algorithm {algorithm_tag:?} is not supported"))
+    })?;
+    if !is_valid_digest(hex) {
+        return Err(Error::invalid_request(
+            "inputDigest must be a 64-character lowercase hexadecimal SHA-256 digest",
+        ));
+    }
+    Ok((algorithm, hex.to_owned()))
+}
+
+/// Reject anything short of a fully-qualified `http`/`https` URI with no
+/// embedded credentials, a non-empty host, and no fragment.
+///
+/// Each failure class gets its own message so callers can tell "not a URL"
+/// apart from "disallowed scheme" or "embedded credentials", the same
+/// per-failure-class discipline used for the WebSocket Origin allow-list.
+pub(super) fn validate_source_url(url: &Url) -> Result<(), Error> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(Error::invalid_request(
+            "sourceUrl must use the http or https scheme",
+        ));
+    }
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(Error::invalid_request(
+            "sourceUrl must not contain embedded credentials",
+        ));
+    }
+    if !url.host_str().is_some_and(|host| !host.is_empty()) {
+        return Err(Error::invalid_request("sourceUrl must have a non-empty host"));
+    }
+    if url.fragment().is_some() {
+        return Err(Error::invalid_request("sourceUrl must not contain a fragment"));
+    }
+    Ok(())
+}
+
 pub(super) fn validate_bounds(bounds: [f64; 4]) -> Result<(), Error> {
     let [min_lng, min_lat, max_lng, max_lat] = bounds;
     validate_longitude_bounds(min_lng, max_lng)?;
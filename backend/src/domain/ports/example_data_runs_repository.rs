@@ -3,6 +3,20 @@
 //! This port provides the interface for recording and querying which example
 //! data seeds have been applied to the database. It guards against duplicate
 //! seeding on concurrent startups or restarts.
+//!
+//! Recording is a two-phase, crash-safe lifecycle rather than a single
+//! insert: [`ExampleDataRunsRepository::try_record_seed`] claims a seed key
+//! by inserting a `pending` row, and only
+//! [`ExampleDataRunsRepository::finalize_seed`] transitions it to
+//! `completed` once the user/preference rows have actually landed. A
+//! process that crashes between the two leaves the row `pending` rather
+//! than falsely `completed`, so [`ExampleDataRunsRepository::is_seeded`]
+//! correctly reports it as unseeded and
+//! [`ExampleDataRunsRepository::reclaim_abandoned_seed`] can reset it back
+//! to claimable once it has sat pending for longer than a restart should
+//! take.
+
+use std::time::Duration;
 
 use async_trait::async_trait;
 
@@ -53,10 +67,21 @@ pub fn try_seed_to_i64(seed: u64) -> Result<i64, ExampleDataRunsError> {
 /// the latter as an error condition.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SeedingResult {
-    /// Seed was newly recorded; proceed with seeding.
+    /// Seed was newly claimed as `pending`; proceed with seeding, then call
+    /// [`ExampleDataRunsRepository::finalize_seed`].
     Applied,
     /// Seed was already recorded; skip seeding.
-    AlreadySeeded,
+    ///
+    /// Carries the `user_count` and `seed` columns from the existing
+    /// conflicting row, so callers can detect drift by comparing them
+    /// against the `SeedDefinition` they intended to apply and warn when a
+    /// seed key has been reused with different parameters.
+    AlreadySeeded {
+        /// `user_count` recorded by the historical run.
+        recorded_user_count: i32,
+        /// RNG `seed` recorded by the historical run.
+        recorded_seed: i64,
+    },
 }
 
 /// Port for tracking example data seed runs.
@@ -72,19 +97,23 @@ pub enum SeedingResult {
 /// let result = repo.try_record_seed("mossy-owl", 12, 2026).await?;
 /// match result {
 ///     SeedingResult::Applied => {
-///         // Proceed with user/preference insertion
+///         // Proceed with user/preference insertion, then:
+///         repo.finalize_seed("mossy-owl").await?;
 ///     }
-///     SeedingResult::AlreadySeeded => {
-///         // Log and skip
+///     SeedingResult::AlreadySeeded { recorded_user_count, recorded_seed } => {
+///         // Compare against the intended SeedDefinition and warn on drift.
 ///     }
 /// }
 /// ```
+#[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait ExampleDataRunsRepository: Send + Sync {
-    /// Attempt to record a seed run.
+    /// Attempt to claim a seed run by inserting a `pending` row.
     ///
-    /// Returns `Applied` if the record was inserted (seed not previously run),
-    /// or `AlreadySeeded` if it already exists (seed was previously run).
+    /// Returns `Applied` if the record was inserted (seed not previously
+    /// claimed), or `AlreadySeeded` with the conflicting row's recorded
+    /// `user_count`/`seed` if a `pending` or `completed` row already exists
+    /// (seed was previously claimed or run).
     ///
     /// # Arguments
     ///
@@ -98,10 +127,37 @@ pub trait ExampleDataRunsRepository: Send + Sync {
         seed: i64,
     ) -> Result<SeedingResult, ExampleDataRunsError>;
 
-    /// Check if a seed has already been applied.
+    /// Transition a claimed seed run from `pending` to `completed`.
     ///
-    /// Returns `true` if the seed exists in the database, `false` otherwise.
+    /// Callers must only invoke this after the user/preference rows for the
+    /// seed have been successfully persisted.
+    async fn finalize_seed(&self, seed_key: &str) -> Result<(), ExampleDataRunsError>;
+
+    /// Check if a seed has already been fully applied.
+    ///
+    /// Returns `true` only for `completed` rows; a `pending` row (one whose
+    /// seeding was interrupted) is reported as not seeded so it can be
+    /// retried.
     async fn is_seeded(&self, seed_key: &str) -> Result<bool, ExampleDataRunsError>;
+
+    /// Reset a `pending` seed run back to claimable if it has been pending
+    /// for longer than `stale_after`.
+    ///
+    /// Deletes the stale row outright rather than merely refreshing it:
+    /// `try_record_seed` claims via `INSERT ... ON CONFLICT (seed_key) DO
+    /// NOTHING`, so a row left in place — even with a fresh `claimed_at` —
+    /// would keep conflicting with every future claim attempt forever.
+    /// Removing it is what actually makes the seed key claimable again.
+    ///
+    /// Returns `true` if a stale `pending` row was reclaimed, `false` if the
+    /// row is missing, still fresh, or already `completed`. Intended to run
+    /// at startup so a crashed worker's interrupted seed is redone rather
+    /// than permanently stuck.
+    async fn reclaim_abandoned_seed(
+        &self,
+        seed_key: &str,
+        stale_after: Duration,
+    ) -> Result<bool, ExampleDataRunsError>;
 }
 
 /// Test fixture implementation that always reports seeds as not yet applied.
@@ -122,9 +178,21 @@ impl ExampleDataRunsRepository for FixtureExampleDataRunsRepository {
         Ok(SeedingResult::Applied)
     }
 
+    async fn finalize_seed(&self, _seed_key: &str) -> Result<(), ExampleDataRunsError> {
+        Ok(())
+    }
+
     async fn is_seeded(&self, _seed_key: &str) -> Result<bool, ExampleDataRunsError> {
         Ok(false)
     }
+
+    async fn reclaim_abandoned_seed(
+        &self,
+        _seed_key: &str,
+        _stale_after: Duration,
+    ) -> Result<bool, ExampleDataRunsError> {
+        Ok(false)
+    }
 }
 
 #[cfg(test)]
@@ -149,6 +217,24 @@ mod tests {
         assert!(matches!(result, Ok(false)));
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn fixture_repository_finalize_seed_succeeds() {
+        let repo = FixtureExampleDataRunsRepository;
+        let result = repo.finalize_seed("test-seed").await;
+        assert!(result.is_ok());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn fixture_repository_reclaim_abandoned_seed_reports_false() {
+        let repo = FixtureExampleDataRunsRepository;
+        let result = repo
+            .reclaim_abandoned_seed("test-seed", Duration::from_secs(300))
+            .await;
+        assert!(matches!(result, Ok(false)));
+    }
+
     #[rstest]
     fn try_seed_to_i64_converts_valid_values() {
         assert_eq!(try_seed_to_i64(0).expect("convert 0 to i64"), 0);
@@ -1,14 +1,18 @@
 //! Port abstraction for applying example data seeds.
 //!
-//! This port encapsulates the transactional persistence needed to seed example
-//! users and their preferences while recording the seed run. Adapters should
-//! ensure the seed run insert and user/preference inserts occur atomically.
+//! This port encapsulates the transactional persistence needed to upsert
+//! generated example users and their preferences. It does not manage the
+//! seed run's lifecycle itself: callers claim and finalize the run via
+//! [`super::ExampleDataRunsRepository`] before and after calling
+//! [`ExampleDataSeedRepository::seed_example_data`], so a crash partway
+//! through generation leaves the run `pending` (retryable) rather than
+//! silently skipped.
 
 use async_trait::async_trait;
 
 use crate::domain::{User, UserPreferences};
 
-use super::{SeedingResult, define_port_error};
+use super::define_port_error;
 
 define_port_error! {
     /// Persistence errors raised by example data seed repository adapters.
@@ -31,7 +35,8 @@ pub struct ExampleDataSeedUser {
 
 /// Request payload for applying a seed run.
 pub struct ExampleDataSeedRequest {
-    /// Seed name recorded in the seed run table.
+    /// Seed name the run was claimed under. Carried through for logging;
+    /// this adapter does not touch the seed run table.
     pub seed_key: String,
     /// Number of users generated for the seed.
     pub user_count: i32,
@@ -41,20 +46,19 @@ pub struct ExampleDataSeedRequest {
     pub users: Vec<ExampleDataSeedUser>,
 }
 
-/// Port for applying example data seeds in a single transaction.
+/// Port for persisting generated example users in a single transaction.
 ///
 /// Implementations must:
-/// - Insert a seed run record guarded by `ON CONFLICT DO NOTHING`.
 /// - Insert or upsert user records.
 /// - Insert or upsert user preference records.
 /// - Roll back all changes if any step fails.
+///
+/// Callers must only invoke this after claiming the seed run via
+/// [`super::ExampleDataRunsRepository::try_record_seed`].
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait ExampleDataSeedRepository: Send + Sync {
-    /// Apply a seed run and persist the generated example users.
-    ///
-    /// Returns `Applied` when the seed run is recorded and data is inserted,
-    /// or `AlreadySeeded` when the seed key already exists.
+    /// Persist the generated example users and their preferences.
     ///
     /// # Examples
     ///
@@ -62,7 +66,7 @@ pub trait ExampleDataSeedRepository: Send + Sync {
     /// use async_trait::async_trait;
     /// use backend::domain::ports::{
     ///     ExampleDataSeedRepository, ExampleDataSeedRepositoryError, ExampleDataSeedRequest,
-    ///     ExampleDataSeedUser, SeedingResult,
+    ///     ExampleDataSeedUser,
     /// };
     /// use backend::domain::{DisplayName, User, UserId, UserPreferencesBuilder};
     /// use uuid::Uuid;
@@ -73,13 +77,9 @@ pub trait ExampleDataSeedRepository: Send + Sync {
     /// impl ExampleDataSeedRepository for Repo {
     ///     async fn seed_example_data(
     ///         &self,
-    ///         request: ExampleDataSeedRequest,
-    ///     ) -> Result<SeedingResult, ExampleDataSeedRepositoryError> {
-    ///         Ok(if request.seed_key == "mossy-owl" {
-    ///             SeedingResult::Applied
-    ///         } else {
-    ///             SeedingResult::AlreadySeeded
-    ///         })
+    ///         _request: ExampleDataSeedRequest,
+    ///     ) -> Result<(), ExampleDataSeedRepositoryError> {
+    ///         Ok(())
     ///     }
     /// }
     ///
@@ -93,13 +93,12 @@ pub trait ExampleDataSeedRepository: Send + Sync {
     ///     seed: 42,
     ///     users: vec![ExampleDataSeedUser { user, preferences }],
     /// };
-    /// let result = Repo.seed_example_data(request).await?;
-    /// assert_eq!(result, SeedingResult::Applied);
+    /// Repo.seed_example_data(request).await?;
     /// # Ok(())
     /// # }
     /// ```
     async fn seed_example_data(
         &self,
         request: ExampleDataSeedRequest,
-    ) -> Result<SeedingResult, ExampleDataSeedRepositoryError>;
+    ) -> Result<(), ExampleDataSeedRepositoryError>;
 }
@@ -0,0 +1,137 @@
+//! Port abstraction for a durable, Postgres-backed background job queue.
+//!
+//! Example-data seeding, route precomputation, and POI imports all want to
+//! run off the request path and survive restarts, so this port models a
+//! general-purpose work queue alongside [`super::ExampleDataRunsRepository`]:
+//! jobs move from `new` to `running` as workers claim them, and
+//! [`JobQueueRepository::release_stale`] reclaims jobs whose worker crashed
+//! without completing or renewing its heartbeat.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::define_port_error;
+
+define_port_error! {
+    /// Persistence errors raised by job queue repository adapters.
+    pub enum JobQueueError {
+        /// Repository connection could not be established.
+        Connection { message: String } => "job queue connection failed: {message}",
+        /// Query or mutation failed during execution.
+        Query { message: String } => "job queue query failed: {message}",
+    }
+}
+
+/// A job claimed from the queue by a worker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimedJob {
+    /// Row identifier, used for `heartbeat`/`complete` calls.
+    pub id: i64,
+    /// Queue name the job was claimed from.
+    pub queue: String,
+    /// Job payload, as enqueued.
+    pub payload: serde_json::Value,
+}
+
+/// Port for a durable, heartbeat-leased background job queue.
+///
+/// Implementations must claim jobs atomically (e.g. `SELECT ... FOR UPDATE
+/// SKIP LOCKED`) so concurrent workers never process the same row twice.
+#[async_trait]
+pub trait JobQueueRepository: Send + Sync {
+    /// Enqueue a new job with the given JSON payload.
+    async fn enqueue(&self, queue: &str, payload: serde_json::Value) -> Result<(), JobQueueError>;
+
+    /// Atomically claim the oldest unclaimed job on `queue`, if any.
+    ///
+    /// Marks the claimed row `running`, owned by `worker_id`, with a fresh
+    /// heartbeat.
+    async fn claim(
+        &self,
+        queue: &str,
+        worker_id: &str,
+    ) -> Result<Option<ClaimedJob>, JobQueueError>;
+
+    /// Renew the heartbeat on a job this worker still holds.
+    async fn heartbeat(&self, job_id: i64) -> Result<(), JobQueueError>;
+
+    /// Mark a job as finished, removing it from the queue.
+    async fn complete(&self, job_id: i64) -> Result<(), JobQueueError>;
+
+    /// Reset `running` jobs whose heartbeat is older than `older_than` back
+    /// to `new`, so a crashed worker's jobs are retried by someone else.
+    ///
+    /// Returns the number of jobs released.
+    async fn release_stale(&self, older_than: DateTime<Utc>) -> Result<u64, JobQueueError>;
+}
+
+/// Test fixture implementation that never has work to claim.
+///
+/// Useful for unit testing code that depends on the repository without
+/// requiring a real database connection.
+#[derive(Debug, Default, Clone)]
+pub struct FixtureJobQueueRepository;
+
+#[async_trait]
+impl JobQueueRepository for FixtureJobQueueRepository {
+    async fn enqueue(
+        &self,
+        _queue: &str,
+        _payload: serde_json::Value,
+    ) -> Result<(), JobQueueError> {
+        Ok(())
+    }
+
+    async fn claim(
+        &self,
+        _queue: &str,
+        _worker_id: &str,
+    ) -> Result<Option<ClaimedJob>, JobQueueError> {
+        Ok(None)
+    }
+
+    async fn heartbeat(&self, _job_id: i64) -> Result<(), JobQueueError> {
+        Ok(())
+    }
+
+    async fn complete(&self, _job_id: i64) -> Result<(), JobQueueError> {
+        Ok(())
+    }
+
+    async fn release_stale(&self, _older_than: DateTime<Utc>) -> Result<u64, JobQueueError> {
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Regression coverage for this module.
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[tokio::test]
+    async fn fixture_repository_never_claims_work() {
+        let repo = FixtureJobQueueRepository;
+        let result = repo.claim("example-data-seed", "worker-1").await;
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn fixture_repository_enqueue_succeeds() {
+        let repo = FixtureJobQueueRepository;
+        let result = repo
+            .enqueue("example-data-seed", serde_json::json!({"seed_key": "mossy-owl"}))
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn fixture_repository_release_stale_reports_none_released() {
+        let repo = FixtureJobQueueRepository;
+        let result = repo.release_stale(Utc::now()).await;
+        assert_eq!(result.expect("release_stale succeeds"), 0);
+    }
+}
@@ -4,8 +4,13 @@ mod macros;
 pub(crate) use macros::define_port_error;
 
 mod cache_key;
+mod example_data_runs_repository;
+mod example_data_seed_repository;
 mod idempotency_store;
+mod job_queue_repository;
 mod login_service;
+mod migrations_repository;
+mod route_annotations_telemetry;
 mod route_cache;
 mod route_metrics;
 mod route_queue;
@@ -19,10 +24,33 @@ mod users_query;
 
 pub use cache_key::{RouteCacheKey, RouteCacheKeyValidationError};
 #[cfg(test)]
+pub use example_data_runs_repository::MockExampleDataRunsRepository;
+pub use example_data_runs_repository::{
+    try_seed_to_i64, ExampleDataRunsError, ExampleDataRunsRepository,
+    FixtureExampleDataRunsRepository, SeedingResult,
+};
+#[cfg(test)]
+pub use example_data_seed_repository::MockExampleDataSeedRepository;
+pub use example_data_seed_repository::{
+    ExampleDataSeedRepository, ExampleDataSeedRepositoryError, ExampleDataSeedRequest,
+    ExampleDataSeedUser,
+};
+#[cfg(test)]
 pub use idempotency_store::MockIdempotencyStore;
 pub use idempotency_store::{FixtureIdempotencyStore, IdempotencyStore, IdempotencyStoreError};
+pub use job_queue_repository::{
+    ClaimedJob, FixtureJobQueueRepository, JobQueueError, JobQueueRepository,
+};
 pub use login_service::{FixtureLoginService, LoginService};
-pub use route_cache::{RouteCache, RouteCacheError};
+pub use migrations_repository::{
+    FixtureMigrationsRepository, Migration, MigrationsRepository, MigrationsRepositoryError,
+};
+#[cfg(test)]
+pub use route_annotations_telemetry::MockRouteAnnotationsTelemetry;
+pub use route_annotations_telemetry::{
+    NoOpRouteAnnotationsTelemetry, RouteAnnotationsTelemetry, RouteAnnotationsTelemetryError,
+};
+pub use route_cache::{CacheIntegrityError, RouteCache, RouteCacheError};
 pub use route_metrics::{RouteMetrics, RouteMetricsError};
 pub use route_queue::{JobDispatchError, RouteQueue};
 pub use route_repository::{RoutePersistenceError, RouteRepository};
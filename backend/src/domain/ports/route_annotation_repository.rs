@@ -124,6 +124,65 @@ pub trait RouteAnnotationRepository: Send + Sync {
         progress: &RouteProgress,
         expected_revision: Option<u32>,
     ) -> Result<(), RouteAnnotationRepositoryError>;
+
+    /// Apply a batch of already-validated writes atomically in a single
+    /// database transaction.
+    ///
+    /// If any write fails (including an optimistic concurrency check that
+    /// only this write's execution can discover, such as a revision bumped
+    /// by a concurrent request between planning and commit), none of the
+    /// batch's writes are persisted. On success, returns one outcome per
+    /// write, in the same order as `writes`. On failure, returns the index
+    /// of the first write that failed together with its error.
+    async fn apply_batch(
+        &self,
+        writes: &[AnnotationBatchWrite],
+    ) -> Result<Vec<AnnotationBatchWriteOutcome>, (usize, RouteAnnotationRepositoryError)>;
+}
+
+/// A single write planned as part of an annotation batch.
+///
+/// Built by the caller after validating each operation (authorisation,
+/// revision conflicts) against data read before the transaction begins; the
+/// write itself re-checks optimistic concurrency at commit time via the same
+/// revision semantics as [`RouteAnnotationRepository::save_note`] and
+/// [`RouteAnnotationRepository::save_progress`].
+#[derive(Debug, Clone)]
+pub enum AnnotationBatchWrite {
+    /// Insert or update a note, mirroring [`RouteAnnotationRepository::save_note`].
+    UpsertNote {
+        /// The note to persist.
+        note: RouteNote,
+        /// Expected current revision, or `None` for an insert.
+        expected_revision: Option<u32>,
+    },
+    /// Delete a note by ID, mirroring [`RouteAnnotationRepository::delete_note`].
+    ///
+    /// Deleting a note that no longer exists is a harmless no-op: the
+    /// returned outcome carries `false` rather than failing the batch.
+    DeleteNote {
+        /// The note to delete.
+        note_id: Uuid,
+    },
+    /// Insert or update progress, mirroring
+    /// [`RouteAnnotationRepository::save_progress`].
+    UpdateProgress {
+        /// The progress to persist.
+        progress: RouteProgress,
+        /// Expected current revision, or `None` for an insert.
+        expected_revision: Option<u32>,
+    },
+}
+
+/// Outcome of a single [`AnnotationBatchWrite`] within a successful batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnnotationBatchWriteOutcome {
+    /// The note was inserted or updated.
+    NoteUpserted,
+    /// The note delete ran; `true` if a row was actually removed.
+    NoteDeleted(bool),
+    /// The progress was inserted or updated.
+    ProgressUpdated,
 }
 
 /// Fixture implementation for testing without a real database.
@@ -177,6 +236,24 @@ impl RouteAnnotationRepository for FixtureRouteAnnotationRepository {
     ) -> Result<(), RouteAnnotationRepositoryError> {
         Ok(())
     }
+
+    async fn apply_batch(
+        &self,
+        writes: &[AnnotationBatchWrite],
+    ) -> Result<Vec<AnnotationBatchWriteOutcome>, (usize, RouteAnnotationRepositoryError)> {
+        Ok(writes
+            .iter()
+            .map(|write| match write {
+                AnnotationBatchWrite::UpsertNote { .. } => AnnotationBatchWriteOutcome::NoteUpserted,
+                AnnotationBatchWrite::DeleteNote { .. } => {
+                    AnnotationBatchWriteOutcome::NoteDeleted(false)
+                }
+                AnnotationBatchWrite::UpdateProgress { .. } => {
+                    AnnotationBatchWriteOutcome::ProgressUpdated
+                }
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -276,6 +353,21 @@ mod tests {
             .expect("fixture save should accept progress");
     }
 
+    #[tokio::test]
+    async fn fixture_repository_apply_batch_reports_delete_as_not_found() {
+        let repo = FixtureRouteAnnotationRepository;
+        let writes = vec![AnnotationBatchWrite::DeleteNote {
+            note_id: Uuid::new_v4(),
+        }];
+
+        let outcomes = repo
+            .apply_batch(&writes)
+            .await
+            .expect("fixture batch should accept writes");
+
+        assert_eq!(outcomes, vec![AnnotationBatchWriteOutcome::NoteDeleted(false)]);
+    }
+
     #[rstest]
     fn revision_mismatch_error_formats_correctly() {
         let error = RouteAnnotationRepositoryError::revision_mismatch(3_u32, 7_u32);
@@ -6,12 +6,14 @@
 //! idempotency and optimistic concurrency.
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use uuid::Uuid;
 
-use crate::domain::{Error, IdempotencyKey, RouteNote, RouteProgress, UserId};
+use crate::domain::{Error, IdempotencyKey, RouteNote, RouteProgress, UserId, canonicalize_and_hash};
 
 /// Request to upsert a route note.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpsertNoteRequest {
     /// The note's unique identifier (client-generated).
     pub note_id: Uuid,
@@ -32,6 +34,26 @@ pub struct UpsertNoteRequest {
     pub idempotency_key: Option<IdempotencyKey>,
 }
 
+impl UpsertNoteRequest {
+    /// Compute a stable content fingerprint over this request's semantic
+    /// fields: note, route, POI, user and body. `expected_revision` and
+    /// `idempotency_key` are not part of it.
+    ///
+    /// [`RouteAnnotationsService`](crate::domain::RouteAnnotationsService)
+    /// does not use this fingerprint for its idempotency check; it hashes its
+    /// own selection of request fields instead.
+    pub fn payload_fingerprint(&self) -> [u8; 32] {
+        *canonicalize_and_hash(&json!({
+            "noteId": self.note_id,
+            "routeId": self.route_id,
+            "poiId": self.poi_id,
+            "userId": self.user_id,
+            "body": self.body,
+        }))
+        .as_bytes()
+    }
+}
+
 /// Response from upserting a note.
 #[derive(Debug, Clone)]
 pub struct UpsertNoteResponse {
@@ -42,7 +64,7 @@ pub struct UpsertNoteResponse {
 }
 
 /// Request to delete a route note.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteNoteRequest {
     /// The note's unique identifier.
     pub note_id: Uuid,
@@ -52,6 +74,21 @@ pub struct DeleteNoteRequest {
     pub idempotency_key: Option<IdempotencyKey>,
 }
 
+impl DeleteNoteRequest {
+    /// Compute a stable content fingerprint over this request's semantic
+    /// fields.
+    ///
+    /// See [`UpsertNoteRequest::payload_fingerprint`] for how this relates to
+    /// the service's idempotency check.
+    pub fn payload_fingerprint(&self) -> [u8; 32] {
+        *canonicalize_and_hash(&json!({
+            "noteId": self.note_id,
+            "userId": self.user_id,
+        }))
+        .as_bytes()
+    }
+}
+
 /// Response from deleting a note.
 #[derive(Debug, Clone)]
 pub struct DeleteNoteResponse {
@@ -62,7 +99,7 @@ pub struct DeleteNoteResponse {
 }
 
 /// Request to update route progress.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateProgressRequest {
     /// The route being tracked.
     pub route_id: Uuid,
@@ -79,6 +116,22 @@ pub struct UpdateProgressRequest {
     pub idempotency_key: Option<IdempotencyKey>,
 }
 
+impl UpdateProgressRequest {
+    /// Compute a stable content fingerprint over this request's semantic
+    /// fields.
+    ///
+    /// See [`UpsertNoteRequest::payload_fingerprint`] for how this relates to
+    /// the service's idempotency check.
+    pub fn payload_fingerprint(&self) -> [u8; 32] {
+        *canonicalize_and_hash(&json!({
+            "routeId": self.route_id,
+            "userId": self.user_id,
+            "visitedStopIds": self.visited_stop_ids,
+        }))
+        .as_bytes()
+    }
+}
+
 /// Response from updating progress.
 #[derive(Debug, Clone)]
 pub struct UpdateProgressResponse {
@@ -88,6 +141,80 @@ pub struct UpdateProgressResponse {
     pub replayed: bool,
 }
 
+/// One operation within a [`ApplyBatchRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AnnotationBatchOperation {
+    /// Upsert a route note. See [`RouteAnnotationsCommand::upsert_note`].
+    UpsertNote(UpsertNoteRequest),
+    /// Delete a route note. See [`RouteAnnotationsCommand::delete_note`].
+    DeleteNote(DeleteNoteRequest),
+    /// Update route progress. See [`RouteAnnotationsCommand::update_progress`].
+    UpdateProgress(UpdateProgressRequest),
+}
+
+impl AnnotationBatchOperation {
+    fn payload_fingerprint(&self) -> [u8; 32] {
+        match self {
+            Self::UpsertNote(request) => request.payload_fingerprint(),
+            Self::DeleteNote(request) => request.payload_fingerprint(),
+            Self::UpdateProgress(request) => request.payload_fingerprint(),
+        }
+    }
+}
+
+/// Request to apply an ordered batch of annotation operations under one
+/// idempotency key.
+///
+/// Operations are validated in order, then committed atomically: either all
+/// of them are applied, or (on the first failure) none of them are. See
+/// [`RouteAnnotationsCommand::apply_batch`] for the full error contract.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyBatchRequest {
+    /// The user performing the batch.
+    pub user_id: UserId,
+    /// Operations to apply, in order.
+    pub operations: Vec<AnnotationBatchOperation>,
+    /// Idempotency key covering the whole batch.
+    pub idempotency_key: IdempotencyKey,
+}
+
+impl ApplyBatchRequest {
+    /// Compute a stable content fingerprint over the ordered batch.
+    ///
+    /// Combines each operation's own fingerprint (see
+    /// [`UpsertNoteRequest::payload_fingerprint`]) in order, so reordering,
+    /// inserting, or changing any operation changes the batch fingerprint.
+    pub fn payload_fingerprint(&self) -> [u8; 32] {
+        let fingerprints: Vec<String> = self
+            .operations
+            .iter()
+            .map(|operation| hex::encode(operation.payload_fingerprint()))
+            .collect();
+        *canonicalize_and_hash(&json!({ "operations": fingerprints })).as_bytes()
+    }
+}
+
+/// Outcome of a single operation within a batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AnnotationBatchItemResult {
+    /// Result of an [`AnnotationBatchOperation::UpsertNote`].
+    UpsertNote(RouteNote),
+    /// Result of an [`AnnotationBatchOperation::DeleteNote`].
+    DeleteNote { deleted: bool },
+    /// Result of an [`AnnotationBatchOperation::UpdateProgress`].
+    UpdateProgress(RouteProgress),
+}
+
+/// Response from applying a batch of annotation operations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyBatchResponse {
+    /// Per-item results, in the same order as the request's `operations`.
+    pub items: Vec<AnnotationBatchItemResult>,
+    /// Whether this response was replayed from a previous idempotent request.
+    pub replayed: bool,
+}
+
 /// Driving port for route annotation operations.
 ///
 /// This port is consumed by inbound adapters (e.g., HTTP handlers) to manage
@@ -98,8 +225,11 @@ pub struct UpdateProgressResponse {
 ///
 /// When an `idempotency_key` is provided, the implementation should:
 /// 1. Check if a response for this key already exists.
-/// 2. If so, return the cached response with `replayed: true`.
-/// 3. If not, perform the operation and cache the response.
+/// 2. If so, compare the payload hash stored with it to the incoming
+///    request's payload hash: on a match, return the cached response with
+///    `replayed: true`; on a mismatch, fail with a conflict error.
+/// 3. If not, perform the operation and cache the response alongside the
+///    request's payload hash.
 ///
 /// # Optimistic Concurrency
 ///
@@ -148,6 +278,24 @@ pub trait RouteAnnotationsCommand: Send + Sync {
         &self,
         request: UpdateProgressRequest,
     ) -> Result<UpdateProgressResponse, Error>;
+
+    /// Apply an ordered batch of annotation operations under one idempotency
+    /// key covering the whole batch.
+    ///
+    /// Operations are validated in order and then committed in a single
+    /// database transaction: if any operation fails validation, or any write
+    /// fails at commit time (e.g. a revision bumped by a concurrent
+    /// request), none of the batch's operations are persisted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Any operation's revision check fails (conflict), in which case the
+    ///   whole batch is rejected and the error's details identify the
+    ///   failing operation's index under `index`.
+    /// - The idempotency key was used with a different payload (conflict).
+    /// - A database or connection error occurs.
+    async fn apply_batch(&self, request: ApplyBatchRequest) -> Result<ApplyBatchResponse, Error>;
 }
 
 /// Fixture implementation for testing.
@@ -201,6 +349,33 @@ impl RouteAnnotationsCommand for FixtureRouteAnnotationsCommand {
             replayed: false,
         })
     }
+
+    async fn apply_batch(&self, request: ApplyBatchRequest) -> Result<ApplyBatchResponse, Error> {
+        let mut items = Vec::with_capacity(request.operations.len());
+        for operation in request.operations {
+            let item = match operation {
+                AnnotationBatchOperation::UpsertNote(request) => {
+                    AnnotationBatchItemResult::UpsertNote(self.upsert_note(request).await?.note)
+                }
+                AnnotationBatchOperation::DeleteNote(request) => {
+                    AnnotationBatchItemResult::DeleteNote {
+                        deleted: self.delete_note(request).await?.deleted,
+                    }
+                }
+                AnnotationBatchOperation::UpdateProgress(request) => {
+                    AnnotationBatchItemResult::UpdateProgress(
+                        self.update_progress(request).await?.progress,
+                    )
+                }
+            };
+            items.push(item);
+        }
+
+        Ok(ApplyBatchResponse {
+            items,
+            replayed: false,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -300,4 +475,156 @@ mod tests {
 
         assert_eq!(response.progress.revision, 6);
     }
+
+    #[test]
+    fn upsert_note_fingerprint_ignores_expected_revision() {
+        let base = UpsertNoteRequest {
+            note_id: Uuid::new_v4(),
+            route_id: Uuid::new_v4(),
+            poi_id: None,
+            user_id: UserId::random(),
+            body: "Same content".to_owned(),
+            expected_revision: Some(1),
+            idempotency_key: None,
+        };
+        let mut bumped_revision = base.clone();
+        bumped_revision.expected_revision = Some(2);
+
+        assert_eq!(
+            base.payload_fingerprint(),
+            bumped_revision.payload_fingerprint()
+        );
+    }
+
+    #[test]
+    fn upsert_note_fingerprint_differs_on_body_change() {
+        let mut request = UpsertNoteRequest {
+            note_id: Uuid::new_v4(),
+            route_id: Uuid::new_v4(),
+            poi_id: None,
+            user_id: UserId::random(),
+            body: "Original".to_owned(),
+            expected_revision: None,
+            idempotency_key: None,
+        };
+        let original_fingerprint = request.payload_fingerprint();
+
+        request.body = "Changed".to_owned();
+
+        assert_ne!(original_fingerprint, request.payload_fingerprint());
+    }
+
+    #[test]
+    fn delete_note_fingerprint_differs_per_note() {
+        let user_id = UserId::random();
+        let first = DeleteNoteRequest {
+            note_id: Uuid::new_v4(),
+            user_id: user_id.clone(),
+            idempotency_key: None,
+        };
+        let second = DeleteNoteRequest {
+            note_id: Uuid::new_v4(),
+            user_id,
+            idempotency_key: None,
+        };
+
+        assert_ne!(first.payload_fingerprint(), second.payload_fingerprint());
+    }
+
+    #[test]
+    fn update_progress_fingerprint_differs_on_visited_stops() {
+        let mut request = UpdateProgressRequest {
+            route_id: Uuid::new_v4(),
+            user_id: UserId::random(),
+            visited_stop_ids: vec![Uuid::new_v4()],
+            expected_revision: None,
+            idempotency_key: None,
+        };
+        let original_fingerprint = request.payload_fingerprint();
+
+        request.visited_stop_ids.push(Uuid::new_v4());
+
+        assert_ne!(original_fingerprint, request.payload_fingerprint());
+    }
+
+    fn idempotency_key() -> IdempotencyKey {
+        IdempotencyKey::new(Uuid::new_v4().to_string()).expect("valid UUID")
+    }
+
+    #[tokio::test]
+    async fn fixture_command_applies_batch_in_order() {
+        let command = FixtureRouteAnnotationsCommand;
+        let user_id = UserId::random();
+        let note_request = UpsertNoteRequest {
+            note_id: Uuid::new_v4(),
+            route_id: Uuid::new_v4(),
+            poi_id: None,
+            user_id: user_id.clone(),
+            body: "Batched note".to_owned(),
+            expected_revision: None,
+            idempotency_key: None,
+        };
+        let progress_request = UpdateProgressRequest {
+            route_id: Uuid::new_v4(),
+            user_id,
+            visited_stop_ids: vec![Uuid::new_v4()],
+            expected_revision: None,
+            idempotency_key: None,
+        };
+        let request = ApplyBatchRequest {
+            user_id: UserId::random(),
+            operations: vec![
+                AnnotationBatchOperation::UpsertNote(note_request),
+                AnnotationBatchOperation::UpdateProgress(progress_request),
+            ],
+            idempotency_key: idempotency_key(),
+        };
+
+        let response = command.apply_batch(request).await.expect("should succeed");
+
+        assert!(!response.replayed);
+        assert_eq!(response.items.len(), 2);
+        assert!(matches!(
+            response.items[0],
+            AnnotationBatchItemResult::UpsertNote(_)
+        ));
+        assert!(matches!(
+            response.items[1],
+            AnnotationBatchItemResult::UpdateProgress(_)
+        ));
+    }
+
+    #[test]
+    fn batch_fingerprint_differs_on_operation_order() {
+        let user_id = UserId::random();
+        let first = AnnotationBatchOperation::UpsertNote(UpsertNoteRequest {
+            note_id: Uuid::new_v4(),
+            route_id: Uuid::new_v4(),
+            poi_id: None,
+            user_id: user_id.clone(),
+            body: "First".to_owned(),
+            expected_revision: None,
+            idempotency_key: None,
+        });
+        let second = AnnotationBatchOperation::DeleteNote(DeleteNoteRequest {
+            note_id: Uuid::new_v4(),
+            user_id,
+            idempotency_key: None,
+        });
+        let forward = ApplyBatchRequest {
+            user_id: UserId::random(),
+            operations: vec![first.clone(), second.clone()],
+            idempotency_key: idempotency_key(),
+        };
+        let reversed = ApplyBatchRequest {
+            user_id: forward.user_id.clone(),
+            operations: vec![second, first],
+            idempotency_key: forward.idempotency_key.clone(),
+        };
+
+        assert_ne!(
+            forward.payload_fingerprint(),
+            reversed.payload_fingerprint()
+        );
+    }
 }
@@ -0,0 +1,146 @@
+//! Domain port surface for route annotation idempotency/concurrency metrics.
+//!
+//! `RouteAnnotationsService` encodes exactly the events an operator wants to
+//! watch - fresh executions, replays, payload-hash conflicts, duplicate-key
+//! store races, and revision mismatches - but recording them depends on a
+//! metrics backend the domain layer should not know about. This port lets
+//! the service emit counters without coupling to a specific exporter.
+
+use async_trait::async_trait;
+
+use super::define_port_error;
+use crate::domain::MutationType;
+
+define_port_error! {
+    /// Errors exposed when recording route annotation telemetry.
+    pub enum RouteAnnotationsTelemetryError {
+        /// Metric exporter rejected the write.
+        Export { message: String } => "route annotations telemetry exporter failed: {message}",
+    }
+}
+
+/// Metrics recording port for route annotation idempotency/concurrency
+/// lifecycle events.
+///
+/// Each hook is tagged with the [`MutationType`] of the request that
+/// triggered it, so operators can break down replay rates and contention by
+/// mutation kind.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RouteAnnotationsTelemetry: Send + Sync {
+    /// Record a fresh execution (no cached response existed for the key).
+    async fn record_fresh(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError>;
+
+    /// Record a replay of a cached response for a matching payload.
+    async fn record_replay(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError>;
+
+    /// Record an idempotency key reused with a different payload.
+    async fn record_conflict(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError>;
+
+    /// Record a duplicate-key store race resolved via the database's
+    /// uniqueness constraint.
+    async fn record_duplicate_race(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError>;
+
+    /// Record an optimistic-concurrency revision mismatch.
+    async fn record_revision_mismatch(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError>;
+}
+
+/// No-op implementation for when telemetry is disabled or in tests.
+///
+/// All methods immediately return `Ok(())` without side effects.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoOpRouteAnnotationsTelemetry;
+
+#[async_trait]
+impl RouteAnnotationsTelemetry for NoOpRouteAnnotationsTelemetry {
+    async fn record_fresh(
+        &self,
+        _mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        Ok(())
+    }
+
+    async fn record_replay(
+        &self,
+        _mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        Ok(())
+    }
+
+    async fn record_conflict(
+        &self,
+        _mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        Ok(())
+    }
+
+    async fn record_duplicate_race(
+        &self,
+        _mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        Ok(())
+    }
+
+    async fn record_revision_mismatch(
+        &self,
+        _mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn noop_records_all_events() {
+        let telemetry = NoOpRouteAnnotationsTelemetry;
+
+        assert!(
+            telemetry
+                .record_fresh(MutationType::Notes)
+                .await
+                .is_ok()
+        );
+        assert!(
+            telemetry
+                .record_replay(MutationType::Notes)
+                .await
+                .is_ok()
+        );
+        assert!(
+            telemetry
+                .record_conflict(MutationType::Notes)
+                .await
+                .is_ok()
+        );
+        assert!(
+            telemetry
+                .record_duplicate_race(MutationType::Notes)
+                .await
+                .is_ok()
+        );
+        assert!(
+            telemetry
+                .record_revision_mismatch(MutationType::Notes)
+                .await
+                .is_ok()
+        );
+    }
+}
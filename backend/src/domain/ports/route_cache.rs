@@ -1,5 +1,6 @@
 //! Port interface for caching computed route plans.
 use async_trait::async_trait;
+use thiserror::Error;
 
 use super::{RouteCacheKey, define_port_error};
 
@@ -13,6 +14,26 @@ define_port_error! {
     }
 }
 
+/// Detected mismatch between a cached plan's recomputed payload hash and the
+/// hash stored alongside it when it was written.
+///
+/// Not returned from [`RouteCache::get`]: a mismatch is treated as a cache
+/// miss so callers never receive a silently corrupted plan. Adapters log this
+/// error instead, giving operators a distinct signal (separate from ordinary
+/// misses) for dashboards and alerting on cache corruption.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error(
+    "cached route plan for key '{key}' failed integrity verification: stored hash {expected}, recomputed {actual}"
+)]
+pub struct CacheIntegrityError {
+    /// Key under which the corrupted plan was stored.
+    pub key: RouteCacheKey,
+    /// Hex-encoded hash persisted alongside the plan at write time.
+    pub expected: String,
+    /// Hex-encoded hash recomputed from the plan read back from the cache.
+    pub actual: String,
+}
+
 #[async_trait]
 pub trait RouteCache: Send + Sync {
     /// Domain-specific plan representation shared with the repository.
@@ -1,6 +1,7 @@
 //! Port for walk session persistence and completion summary reads.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::domain::{UserId, WalkCompletionSummary, WalkSession};
@@ -19,6 +20,69 @@ define_port_error! {
     }
 }
 
+/// Opaque, ordering-stable cursor for keyset-paginating completion summaries.
+///
+/// Summaries are ordered by `ended_at` descending, then `session_id`
+/// descending, so the cursor carries both fields to disambiguate summaries
+/// that completed at the same instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SummaryCursor {
+    ended_at: DateTime<Utc>,
+    session_id: Uuid,
+}
+
+impl SummaryCursor {
+    /// Construct a cursor from a summary's ordering key.
+    pub fn new(ended_at: DateTime<Utc>, session_id: Uuid) -> Self {
+        Self {
+            ended_at,
+            session_id,
+        }
+    }
+
+    /// The completion timestamp of the summary this cursor was taken from.
+    pub fn ended_at(&self) -> DateTime<Utc> {
+        self.ended_at
+    }
+
+    /// The session id of the summary this cursor was taken from.
+    pub fn session_id(&self) -> Uuid {
+        self.session_id
+    }
+}
+
+impl From<&WalkCompletionSummary> for SummaryCursor {
+    fn from(summary: &WalkCompletionSummary) -> Self {
+        Self::new(summary.ended_at(), summary.session_id())
+    }
+}
+
+/// Query parameters for a single page of completion summaries.
+///
+/// `limit` bounds the number of summaries returned; `since`/`until` bound the
+/// completion timestamp so callers can request slices such as "this month".
+#[derive(Debug, Clone, Default)]
+pub struct SummaryPageQuery {
+    /// Resume after this cursor, excluding the summary it was taken from.
+    pub after: Option<SummaryCursor>,
+    /// Maximum number of summaries to return.
+    pub limit: usize,
+    /// Only include summaries that completed at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only include summaries that completed at or before this time.
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// A single page of completion summaries.
+#[derive(Debug, Clone, Default)]
+pub struct SummaryPage {
+    /// Summaries in this page, ordered newest-first.
+    pub summaries: Vec<WalkCompletionSummary>,
+    /// Cursor to pass as `SummaryPageQuery::after` to fetch the next page, or
+    /// `None` if this was the last page.
+    pub next_cursor: Option<SummaryCursor>,
+}
+
 /// Port for writing walk sessions and reading completion summaries.
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
@@ -32,11 +96,41 @@ pub trait WalkSessionRepository: Send + Sync {
         session_id: &Uuid,
     ) -> Result<Option<WalkSession>, WalkSessionRepositoryError>;
 
-    /// Read completion summaries for a user.
+    /// Read a single, time-bounded, keyset-paginated page of completion
+    /// summaries for a user.
+    ///
+    /// Summaries are ordered by completion time descending; `query.after`
+    /// resumes from a previously returned `SummaryPage::next_cursor`.
+    async fn list_completion_summaries_page(
+        &self,
+        user_id: &UserId,
+        query: SummaryPageQuery,
+    ) -> Result<SummaryPage, WalkSessionRepositoryError>;
+
+    /// Read all completion summaries for a user.
+    ///
+    /// Convenience wrapper over [`list_completion_summaries_page`] that
+    /// requests a single unbounded page. Prefer `list_completion_summaries_page`
+    /// for users with a large walk history.
+    ///
+    /// [`list_completion_summaries_page`]: Self::list_completion_summaries_page
     async fn list_completion_summaries_for_user(
         &self,
         user_id: &UserId,
-    ) -> Result<Vec<WalkCompletionSummary>, WalkSessionRepositoryError>;
+    ) -> Result<Vec<WalkCompletionSummary>, WalkSessionRepositoryError> {
+        let page = self
+            .list_completion_summaries_page(
+                user_id,
+                SummaryPageQuery {
+                    after: None,
+                    limit: usize::MAX,
+                    since: None,
+                    until: None,
+                },
+            )
+            .await?;
+        Ok(page.summaries)
+    }
 }
 
 /// Fixture implementation for tests that do not exercise walk persistence.
@@ -56,11 +150,15 @@ impl WalkSessionRepository for FixtureWalkSessionRepository {
         Ok(None)
     }
 
-    async fn list_completion_summaries_for_user(
+    async fn list_completion_summaries_page(
         &self,
         _user_id: &UserId,
-    ) -> Result<Vec<WalkCompletionSummary>, WalkSessionRepositoryError> {
-        Ok(Vec::new())
+        _query: SummaryPageQuery,
+    ) -> Result<SummaryPage, WalkSessionRepositoryError> {
+        Ok(SummaryPage {
+            summaries: Vec::new(),
+            next_cursor: None,
+        })
     }
 }
 
@@ -139,4 +237,27 @@ mod tests {
         let msg = err.to_string();
         assert!(msg.contains("broken sql"));
     }
+
+    #[rstest]
+    #[tokio::test]
+    async fn fixture_page_returns_empty_with_no_next_cursor() {
+        let repo = FixtureWalkSessionRepository;
+        let page = repo
+            .list_completion_summaries_page(&UserId::random(), SummaryPageQuery::default())
+            .await
+            .expect("fixture page succeeds");
+
+        assert!(page.summaries.is_empty());
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[rstest]
+    fn summary_cursor_round_trips_from_summary() {
+        let session = build_session(UserId::random());
+        let summary = session.completion_summary().expect("completed session");
+        let cursor = SummaryCursor::from(&summary);
+
+        assert_eq!(cursor.ended_at(), summary.ended_at());
+        assert_eq!(cursor.session_id(), summary.session_id());
+    }
 }
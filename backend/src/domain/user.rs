@@ -42,7 +42,7 @@ impl fmt::Display for UserValidationError {
 impl std::error::Error for UserValidationError {}
 
 /// Stable user identifier stored as a UUID.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(try_from = "String", into = "String")]
 pub struct UserId(Uuid, String);
 
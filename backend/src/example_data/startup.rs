@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use cap_std::{ambient_authority, fs::Dir};
 use example_data::{RegistryError, SeedRegistry};
@@ -9,9 +10,17 @@ use mockable::DefaultClock;
 use thiserror::Error;
 use tracing::{info, warn};
 
+use crate::domain::ports::ExampleDataRunsRepository;
 use crate::domain::{ExampleDataSeedOutcome, ExampleDataSeeder, ExampleDataSeedingError};
 use crate::example_data::config::ExampleDataSettings;
-use crate::outbound::persistence::{DbPool, DieselExampleDataSeedRepository};
+use crate::outbound::persistence::{
+    DbPool, DieselExampleDataRunsRepository, DieselExampleDataSeedRepository,
+};
+
+/// How long a `pending` seed run may sit unfinalized before a subsequent
+/// startup treats it as abandoned (e.g. the previous process crashed
+/// mid-seed) and reclaims it for retry.
+const ABANDONED_SEED_RECLAIM_AFTER: Duration = Duration::from_secs(15 * 60);
 
 /// Errors returned while executing startup seeding.
 #[derive(Debug, Error)]
@@ -34,6 +43,9 @@ pub enum StartupSeedingError {
     /// Seed name must not be empty.
     #[error("seed name must not be empty")]
     EmptySeedName,
+    /// Reclaiming an abandoned seed run failed.
+    #[error("example data run reclaim error: {0}")]
+    Reclaim(#[from] crate::domain::ports::ExampleDataRunsError),
 }
 
 /// Apply example data on startup when enabled.
@@ -82,8 +94,23 @@ pub async fn seed_example_data_on_startup(
     let registry_path = settings.registry_path();
     let registry = load_registry(&registry_path)?;
 
+    let runs_repository = DieselExampleDataRunsRepository::new(db_pool.clone());
+    if runs_repository
+        .reclaim_abandoned_seed(seed_name, ABANDONED_SEED_RECLAIM_AFTER)
+        .await?
+    {
+        warn!(
+            seed_key = seed_name,
+            "reclaimed an example data seed run abandoned by a previous startup"
+        );
+    }
+
     let repository = DieselExampleDataSeedRepository::new(db_pool.clone());
-    let seeder = ExampleDataSeeder::new(Arc::new(repository), Arc::new(DefaultClock));
+    let seeder = ExampleDataSeeder::new(
+        Arc::new(repository),
+        Arc::new(runs_repository),
+        Arc::new(DefaultClock),
+    );
     let outcome = seeder
         .seed_from_registry(&registry, seed_name, settings.count)
         .await?;
@@ -96,18 +123,54 @@ pub async fn seed_example_data_on_startup(
                 "example data seeding applied"
             );
         }
-        crate::domain::ports::SeedingResult::AlreadySeeded => {
+        crate::domain::ports::SeedingResult::AlreadySeeded {
+            recorded_user_count,
+            recorded_seed,
+        } => {
             info!(
                 seed_key = %outcome.seed_key,
                 user_count = outcome.user_count,
                 "example data seed already applied; skipping"
             );
+            warn_on_seed_drift(&registry, seed_name, recorded_user_count, recorded_seed);
         }
     }
 
     Ok(Some(outcome))
 }
 
+/// Warn if a historical seed run was recorded with different parameters than
+/// the registry currently defines for `seed_name`, which usually means
+/// example data was regenerated with an incompatible seed/user count under
+/// an already-used seed key.
+fn warn_on_seed_drift(
+    registry: &SeedRegistry,
+    seed_name: &str,
+    recorded_user_count: i32,
+    recorded_seed: i64,
+) {
+    let Ok(seed_def) = registry.find_seed(seed_name) else {
+        return;
+    };
+    let Ok(intended_user_count) = i32::try_from(seed_def.user_count()) else {
+        return;
+    };
+    let Ok(intended_seed) = i64::try_from(seed_def.seed()) else {
+        return;
+    };
+
+    if intended_user_count != recorded_user_count || intended_seed != recorded_seed {
+        warn!(
+            seed_key = seed_name,
+            recorded_user_count,
+            recorded_seed,
+            intended_user_count,
+            intended_seed,
+            "example data seed key reused with different parameters than the recorded run"
+        );
+    }
+}
+
 fn load_registry(path: &Path) -> Result<SeedRegistry, StartupSeedingError> {
     let parent = path.parent().unwrap_or_else(|| Path::new("."));
     let parent = if parent.as_os_str().is_empty() {
@@ -37,6 +37,11 @@ fn status_code_matches_error_code() {
         (Error::unauthorized("no auth"), StatusCode::UNAUTHORIZED),
         (Error::forbidden("denied"), StatusCode::FORBIDDEN),
         (Error::not_found("missing"), StatusCode::NOT_FOUND),
+        (Error::conflict("conflict"), StatusCode::CONFLICT),
+        (
+            Error::service_unavailable("unavailable"),
+            StatusCode::SERVICE_UNAVAILABLE,
+        ),
         (Error::internal("boom"), StatusCode::INTERNAL_SERVER_ERROR),
     ];
     for (err, status) in cases {
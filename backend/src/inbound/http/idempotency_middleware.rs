@@ -0,0 +1,444 @@
+//! HTTP middleware enforcing replay-or-reject idempotency semantics.
+//!
+//! [`Idempotent`] wraps a resource so that mutating requests carrying an
+//! `Idempotency-Key` header are served consistently regardless of retries:
+//!
+//! - No key present: the request passes through unmodified.
+//! - Unseen key: the handler runs and its response is recorded against the
+//!   key's [`PayloadHash`] for future replay.
+//! - Seen key with a matching payload hash: the stored response is replayed
+//!   without re-running the handler.
+//! - Seen key with a conflicting payload hash: the request is rejected with
+//!   `409 Conflict`.
+//!
+//! An in-process guard also prevents two concurrent requests for the same
+//! key from racing the handler; the second is rejected with `409 Conflict`
+//! rather than waiting, since nothing here can observe when the first
+//! finishes without also serialising unrelated requests for other keys.
+
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use actix_session::SessionExt;
+use actix_web::body::{BoxBody, MessageBody, to_bytes};
+use actix_web::dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::StatusCode;
+use actix_web::{Error as ActixError, HttpResponse, ResponseError, web};
+use futures_util::future::{LocalBoxFuture, Ready, ready};
+use serde_json::json;
+use tracing::warn;
+
+use crate::domain::ports::{IdempotencyStore, IdempotencyStoreError};
+use crate::domain::{
+    Error, IdempotencyKey, IdempotencyLookupResult, IdempotencyRecord, MutationType, PayloadHash,
+    UserId, canonicalize_and_hash,
+};
+use crate::inbound::http::idempotency::{extract_idempotency_key, map_idempotency_key_error};
+use crate::inbound::http::session::SessionContext;
+
+/// Middleware factory wrapping a resource with idempotency replay-or-reject
+/// semantics backed by an [`IdempotencyStore`].
+///
+/// Must be registered inside (i.e. `.wrap()`ped after) the session
+/// middleware, since it needs the authenticated user id to scope keys.
+///
+/// # Examples
+/// ```no_run
+/// use actix_web::{web, App};
+/// use backend::domain::idempotency::MutationType;
+/// use backend::domain::ports::FixtureIdempotencyStore;
+/// use backend::inbound::http::idempotency_middleware::Idempotent;
+/// use std::sync::Arc;
+///
+/// let store = Arc::new(FixtureIdempotencyStore);
+/// let _app = App::new().service(
+///     web::scope("/api/v1/widgets").wrap(Idempotent::new(store, MutationType::Notes)),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct Idempotent {
+    store: Arc<dyn IdempotencyStore>,
+    mutation_type: MutationType,
+    in_flight: InFlightGuardSet,
+}
+
+impl Idempotent {
+    /// Wrap a resource with idempotency replay-or-reject semantics.
+    ///
+    /// `mutation_type` is written to each stored record as
+    /// [`IdempotencyRecord::mutation_type`]. Lookups are keyed by idempotency
+    /// key and user only and do not filter on it.
+    pub fn new(store: Arc<dyn IdempotencyStore>, mutation_type: MutationType) -> Self {
+        Self {
+            store,
+            mutation_type,
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotent
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixError> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = ActixError;
+    type InitError = ();
+    type Transform = IdempotentMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotentMiddleware {
+            service: Rc::new(service),
+            store: self.store.clone(),
+            mutation_type: self.mutation_type,
+            in_flight: self.in_flight.clone(),
+        }))
+    }
+}
+
+/// Service wrapper produced by [`Idempotent`].
+///
+/// Applications should not use this type directly.
+pub struct IdempotentMiddleware<S> {
+    service: Rc<S>,
+    store: Arc<dyn IdempotencyStore>,
+    mutation_type: MutationType,
+    in_flight: InFlightGuardSet,
+}
+
+/// Idempotency keys with a request currently in flight, scoped by user so
+/// the same UUID reused by different users does not collide.
+type InFlightGuardSet = Arc<Mutex<HashSet<(UserId, IdempotencyKey)>>>;
+
+/// RAII guard releasing an in-flight reservation when the request finishes,
+/// however it finishes (replay, conflict, success, or handler error).
+struct InFlightGuard {
+    set: InFlightGuardSet,
+    entry: (UserId, IdempotencyKey),
+}
+
+impl InFlightGuard {
+    /// Reserve `entry` for the duration of the request, or return `None` if
+    /// another request already holds it.
+    fn acquire(set: &InFlightGuardSet, user_id: &UserId, key: &IdempotencyKey) -> Option<Self> {
+        let entry = (user_id.clone(), key.clone());
+        #[expect(
+            clippy::expect_used,
+            reason = "poisoning would indicate a prior panic while holding the lock; there is no safe recovery"
+        )]
+        let mut guarded = set.lock().expect("in-flight set mutex poisoned");
+        if guarded.insert(entry.clone()) {
+            drop(guarded);
+            Some(Self {
+                set: set.clone(),
+                entry,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        #[expect(
+            clippy::expect_used,
+            reason = "poisoning would indicate a prior panic while holding the lock; there is no safe recovery"
+        )]
+        let mut guarded = self.set.lock().expect("in-flight set mutex poisoned");
+        guarded.remove(&self.entry);
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotentMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixError> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = ActixError;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let store = self.store.clone();
+        let mutation_type = self.mutation_type;
+        let in_flight = self.in_flight.clone();
+
+        Box::pin(async move {
+            let idempotency_key = match extract_idempotency_key(req.headers()) {
+                Ok(Some(key)) => key,
+                Ok(None) => return forward(service, req).await,
+                Err(err) => return Ok(respond(req, map_idempotency_key_error(err))),
+            };
+
+            let user_id = match SessionContext::new(req.get_session()).require_user_id() {
+                Ok(user_id) => user_id,
+                Err(err) => return Ok(respond(req, err)),
+            };
+
+            let Some(guard) = InFlightGuard::acquire(&in_flight, &user_id, &idempotency_key)
+            else {
+                return Ok(respond(
+                    req,
+                    Error::conflict("a request with this idempotency key is already in progress"),
+                ));
+            };
+
+            let payload_hash = match hash_request_body(&mut req).await {
+                Ok(hash) => hash,
+                Err(err) => return Ok(respond(req, err)),
+            };
+
+            let lookup = store
+                .lookup(&idempotency_key, &user_id, &payload_hash)
+                .await;
+            let response = match lookup {
+                Ok(IdempotencyLookupResult::ConflictingPayload(_)) => Ok(respond(
+                    req,
+                    Error::conflict("idempotency key already used with a different payload")
+                        .with_details(json!({ "idempotencyKey": idempotency_key.to_string() })),
+                )),
+                Ok(IdempotencyLookupResult::MatchingPayload(record)) => {
+                    Ok(respond_with_snapshot(req, &record))
+                }
+                Ok(IdempotencyLookupResult::NotFound) => {
+                    replay_or_record(
+                        service,
+                        store,
+                        req,
+                        IdempotencyRecord {
+                            key: idempotency_key,
+                            mutation_type,
+                            payload_hash,
+                            response_snapshot: serde_json::Value::Null,
+                            user_id,
+                            created_at: chrono::Utc::now(),
+                        },
+                    )
+                    .await
+                }
+                Err(err) => Ok(respond(req, map_store_error(err))),
+            };
+
+            drop(guard);
+            response
+        })
+    }
+}
+
+/// Forward the request to the inner service unchanged, boxing its body.
+async fn forward<S, B>(
+    service: Rc<S>,
+    req: ServiceRequest,
+) -> Result<ServiceResponse<BoxBody>, ActixError>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixError>,
+    B: MessageBody + 'static,
+{
+    Ok(service.call(req).await?.map_into_boxed_body())
+}
+
+/// Build an error response while preserving the request's `HttpRequest`
+/// half, discarding its (possibly already-consumed) payload.
+fn respond(req: ServiceRequest, error: Error) -> ServiceResponse<BoxBody> {
+    let (http_req, _payload) = req.into_parts();
+    ServiceResponse::new(http_req, error.error_response())
+}
+
+/// Replay a stored response snapshot instead of re-running the handler.
+fn respond_with_snapshot(
+    req: ServiceRequest,
+    record: &IdempotencyRecord,
+) -> ServiceResponse<BoxBody> {
+    let (http_req, _payload) = req.into_parts();
+    ServiceResponse::new(http_req, snapshot_to_response(&record.response_snapshot))
+}
+
+/// Read and restore the request body, returning the hash of its canonical
+/// JSON form.
+///
+/// The body is re-buffered onto the request so the wrapped handler still
+/// sees it after this middleware consumes it for hashing.
+async fn hash_request_body(req: &mut ServiceRequest) -> Result<PayloadHash, Error> {
+    let bytes = req
+        .extract::<web::Bytes>()
+        .await
+        .map_err(|err| Error::invalid_request(format!("failed to read request body: {err}")))?;
+
+    let payload_value: serde_json::Value = if bytes.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_slice(&bytes).map_err(|_| {
+            Error::invalid_request("request body must be valid JSON to use an idempotency key")
+        })?
+    };
+
+    req.set_payload(bytes_to_payload(bytes));
+    Ok(canonicalize_and_hash(&payload_value))
+}
+
+/// Run the handler, then record its response against `record` for replay.
+///
+/// Store failures are logged but do not fail the request: the client still
+/// receives the handler's response even if the record could not be
+/// persisted, since the mutation itself already succeeded.
+async fn replay_or_record<S, B>(
+    service: Rc<S>,
+    store: Arc<dyn IdempotencyStore>,
+    req: ServiceRequest,
+    mut record: IdempotencyRecord,
+) -> Result<ServiceResponse<BoxBody>, ActixError>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixError>,
+    B: MessageBody + 'static,
+{
+    let res = service.call(req).await?;
+    let (http_req, res_body) = res.into_parts();
+    let status = res_body.status();
+    let bytes = to_bytes(res_body.into_body())
+        .await
+        .unwrap_or_else(|_| web::Bytes::new());
+
+    record.response_snapshot = response_snapshot(status, &bytes);
+    if let Err(err) = store.store(&record).await {
+        warn!(error = %err, "failed to persist idempotency record");
+    }
+
+    let mut response = HttpResponse::build(status);
+    Ok(ServiceResponse::new(http_req, response.body(bytes)))
+}
+
+/// Serialise a handler's response for storage in an [`IdempotencyRecord`].
+fn response_snapshot(status: StatusCode, body: &web::Bytes) -> serde_json::Value {
+    json!({
+        "status": status.as_u16(),
+        "body": String::from_utf8_lossy(body),
+    })
+}
+
+/// Reconstruct an [`HttpResponse`] from a stored response snapshot.
+fn snapshot_to_response(snapshot: &serde_json::Value) -> HttpResponse {
+    let status = snapshot
+        .get("status")
+        .and_then(serde_json::Value::as_u64)
+        .and_then(|code| u16::try_from(code).ok())
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .unwrap_or(StatusCode::OK);
+    let body = snapshot
+        .get("body")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_owned();
+
+    HttpResponse::build(status)
+        .content_type("application/json")
+        .body(body)
+}
+
+/// Map idempotency store errors to domain errors.
+fn map_store_error(error: IdempotencyStoreError) -> Error {
+    match error {
+        IdempotencyStoreError::Connection { message } => {
+            Error::service_unavailable(format!("idempotency store unavailable: {message}"))
+        }
+        IdempotencyStoreError::Query { message } => {
+            Error::internal(format!("idempotency store error: {message}"))
+        }
+        IdempotencyStoreError::Serialization { message } => {
+            Error::internal(format!("idempotency store serialization failed: {message}"))
+        }
+        IdempotencyStoreError::DuplicateKey { message } => {
+            // The in-flight guard should prevent concurrent inserts for the
+            // same key; a race surfacing here is logged as unexpected.
+            Error::internal(format!("unexpected idempotency key conflict: {message}"))
+        }
+    }
+}
+
+/// Re-wrap buffered bytes as a fresh request payload.
+///
+/// Mirrors the standard Actix pattern for middleware that must read and then
+/// restore a request body (see `actix_http::h1::Payload`).
+fn bytes_to_payload(bytes: web::Bytes) -> Payload {
+    let (_, mut payload) = actix_http::h1::Payload::create(true);
+    payload.unread_data(bytes);
+    Payload::from(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ports::FixtureIdempotencyStore;
+    use crate::inbound::http::test_utils::test_session_middleware;
+    use actix_web::{App, HttpResponse, post, test as actix_test, web};
+
+    #[post("/widgets")]
+    async fn echo_widget(body: web::Bytes) -> HttpResponse {
+        HttpResponse::Created().body(body)
+    }
+
+    fn test_app(
+        store: Arc<dyn IdempotencyStore>,
+    ) -> App<
+        impl actix_web::dev::ServiceFactory<
+                actix_web::dev::ServiceRequest,
+                Config = (),
+                Response = actix_web::dev::ServiceResponse<BoxBody>,
+                Error = actix_web::Error,
+                InitError = (),
+            >,
+    > {
+        App::new().service(
+            web::scope("/api/v1")
+                .wrap(Idempotent::new(store, MutationType::Notes))
+                .wrap(test_session_middleware())
+                .service(echo_widget),
+        )
+    }
+
+    #[actix_web::test]
+    async fn passes_through_requests_without_a_key() {
+        let app = actix_test::init_service(test_app(Arc::new(FixtureIdempotencyStore))).await;
+        let req = actix_test::TestRequest::post()
+            .uri("/api/v1/widgets")
+            .set_payload("hello")
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+    }
+
+    #[actix_web::test]
+    async fn rejects_unauthenticated_requests_carrying_a_key() {
+        let app = actix_test::init_service(test_app(Arc::new(FixtureIdempotencyStore))).await;
+        let req = actix_test::TestRequest::post()
+            .uri("/api/v1/widgets")
+            .insert_header(("Idempotency-Key", IdempotencyKey::random().to_string()))
+            .set_payload("{}")
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn rejects_invalid_idempotency_key_header() {
+        let app = actix_test::init_service(test_app(Arc::new(FixtureIdempotencyStore))).await;
+        let req = actix_test::TestRequest::post()
+            .uri("/api/v1/widgets")
+            .insert_header(("Idempotency-Key", "not-a-uuid"))
+            .set_payload("{}")
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
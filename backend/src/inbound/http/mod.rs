@@ -2,6 +2,8 @@
 
 pub mod error;
 pub mod health;
+pub mod idempotency;
+pub mod idempotency_middleware;
 pub mod routes;
 pub mod schemas;
 pub mod session;
@@ -10,5 +12,8 @@ pub mod state;
 #[cfg(test)]
 pub mod test_utils;
 pub mod users;
+pub mod validation;
+pub mod walk_sessions;
 
 pub use error::ApiResult;
+pub use idempotency_middleware::Idempotent;
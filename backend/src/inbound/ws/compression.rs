@@ -0,0 +1,156 @@
+//! Application-level deflate negotiation and payload codec for `/ws`.
+//!
+//! `actix_ws::Session` only exposes text/binary sends and gives callers no
+//! way to set the WebSocket RSV1 bit, so this adapter can't turn on true
+//! wire-level per-frame compression as specified by `permessage-deflate`
+//! (RFC 7692). Advertising the registered `permessage-deflate` token for a
+//! scheme that doesn't implement RFC 7692 would mislead any
+//! standards-compliant client or intermediary that matches on it, so the
+//! extension negotiated here uses the private [`EXTENSION_TOKEN`] instead.
+//! Once both sides agree on it during the handshake, outbound frames at or
+//! above [`COMPRESSION_THRESHOLD_BYTES`] are deflated and sent as binary
+//! frames, and inbound binary frames are inflated back into the same JSON
+//! commands text frames already carry; smaller frames stay as plain text.
+
+use std::io::{self, Read, Write};
+
+use actix_web::http::header::HeaderValue;
+use flate2::write::DeflateEncoder;
+use flate2::read::DeflateDecoder;
+use flate2::Compression;
+
+/// Frames smaller than this stay uncompressed; deflating tiny payloads
+/// usually grows them once framing overhead is counted.
+pub const COMPRESSION_THRESHOLD_BYTES: usize = 256;
+
+/// Private (non-IANA-registered) extension token advertised during
+/// negotiation.
+///
+/// Deliberately not `permessage-deflate`: that token is registered for RFC
+/// 7692's RSV1-bit frame compression, which this adapter cannot implement
+/// (see module docs). Reusing the registered name would let a genuinely
+/// compliant peer believe it had negotiated RFC 7692 and fail to decode our
+/// frames.
+const EXTENSION_TOKEN: &str = "x-wildside-deflate";
+
+/// Parameters negotiated for one connection's `x-wildside-deflate` extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionNegotiation {
+    server_no_context_takeover: bool,
+    client_no_context_takeover: bool,
+}
+
+impl CompressionNegotiation {
+    /// Parse the client's `Sec-WebSocket-Extensions` offer and negotiate
+    /// parameters, returning `None` when compression is disabled or the
+    /// client didn't offer `x-wildside-deflate` (a `permessage-deflate` offer
+    /// alone is not accepted).
+    pub fn negotiate(enabled: bool, offer: Option<&HeaderValue>) -> Option<Self> {
+        if !enabled {
+            return None;
+        }
+
+        let offer = offer?.to_str().ok()?;
+        offer.split(',').find_map(|candidate| {
+            let mut params = candidate.split(';').map(str::trim);
+            if params.next()? != EXTENSION_TOKEN {
+                return None;
+            }
+
+            let mut negotiation = Self {
+                server_no_context_takeover: true,
+                client_no_context_takeover: false,
+            };
+            for param in params {
+                if param == "client_no_context_takeover" {
+                    negotiation.client_no_context_takeover = true;
+                }
+            }
+            Some(negotiation)
+        })
+    }
+
+    /// Build the `Sec-WebSocket-Extensions` response header value.
+    ///
+    /// Every message is compressed independently (see module docs), so the
+    /// response always advertises `server_no_context_takeover`.
+    pub fn response_header(&self) -> HeaderValue {
+        let mut value = EXTENSION_TOKEN.to_owned();
+        if self.server_no_context_takeover {
+            value.push_str("; server_no_context_takeover");
+        }
+        if self.client_no_context_takeover {
+            value.push_str("; client_no_context_takeover");
+        }
+        HeaderValue::from_str(&value).unwrap_or_else(|_| HeaderValue::from_static(EXTENSION_TOKEN))
+    }
+}
+
+/// Deflate a payload using a fresh, context-free compressor.
+pub fn compress(payload: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(payload)?;
+    encoder.finish()
+}
+
+/// Inflate a payload produced by [`compress`].
+pub fn decompress(payload: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoder = DeflateDecoder::new(payload);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    fn negotiates_offered_extension() {
+        let offer = HeaderValue::from_static("x-wildside-deflate; client_no_context_takeover");
+        let negotiation =
+            CompressionNegotiation::negotiate(true, Some(&offer)).expect("extension offered");
+        assert!(negotiation.client_no_context_takeover);
+        assert!(negotiation.server_no_context_takeover);
+        assert_eq!(
+            negotiation.response_header(),
+            HeaderValue::from_static(
+                "x-wildside-deflate; server_no_context_takeover; client_no_context_takeover"
+            )
+        );
+    }
+
+    #[rstest]
+    fn ignores_real_permessage_deflate_offer() {
+        let offer = HeaderValue::from_static("permessage-deflate");
+        assert!(CompressionNegotiation::negotiate(true, Some(&offer)).is_none());
+    }
+
+    #[rstest]
+    fn skips_negotiation_when_disabled() {
+        let offer = HeaderValue::from_static("x-wildside-deflate");
+        assert!(CompressionNegotiation::negotiate(false, Some(&offer)).is_none());
+    }
+
+    #[rstest]
+    fn skips_negotiation_when_not_offered() {
+        let offer = HeaderValue::from_static("x-other-extension");
+        assert!(CompressionNegotiation::negotiate(true, Some(&offer)).is_none());
+    }
+
+    #[rstest]
+    fn skips_negotiation_when_no_header() {
+        assert!(CompressionNegotiation::negotiate(true, None).is_none());
+    }
+
+    #[rstest]
+    fn round_trips_payload_through_compress_and_decompress() {
+        let payload = br#"{"type":"userCreated","version":1,"displayName":"Alice"}"#;
+        let compressed = compress(payload).expect("compress payload");
+        assert_ne!(compressed, payload);
+
+        let decompressed = decompress(&compressed).expect("decompress payload");
+        assert_eq!(decompressed, payload);
+    }
+}
@@ -1,21 +1,36 @@
 //! Wire-level message definitions for the WebSocket adapter.
 //!
 //! Domain events are transformed into these payloads before being serialized
-//! to JSON and sent to connected clients.
+//! to JSON and sent to connected clients. Every outbound [`ServerEvent`]
+//! carries [`PROTOCOL_VERSION`] so clients can detect a protocol they don't
+//! understand instead of guessing from field shape.
 
 use crate::domain::{DisplayNameRejectedEvent, UserCreatedEvent};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-/// Inbound request payload provided by the client.
+/// Protocol version advertised on every [`ServerEvent`].
+///
+/// Bump this when an existing event's shape changes in a way older clients
+/// can't tolerate; new, purely-additive commands/events don't need a bump.
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// Inbound commands accepted over the WebSocket connection.
+///
+/// Tagged by `type` so the socket can grow new commands (route submission,
+/// onboarding steps, ...) without breaking clients built against an earlier
+/// version: an unrecognised tag falls back to [`ServerEvent::unknown_command`]
+/// instead of tearing down the connection.
 #[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct DisplayNameRequest {
-    /// Client-provided correlation identifier.
-    pub trace_id: Uuid,
-    /// Desired display name.
-    #[serde(alias = "display_name")]
-    pub display_name: String,
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ClientCommand {
+    #[serde(rename_all = "camelCase")]
+    SubmitDisplayName {
+        /// Client-provided correlation identifier.
+        trace_id: Uuid,
+        /// Desired display name.
+        display_name: String,
+    },
 }
 
 /// Outbound payload emitted when a user is created.
@@ -99,6 +114,54 @@ impl From<DisplayNameRejectedEvent> for InvalidDisplayNameResponse {
     }
 }
 
+/// Versioned, tagged envelope for every event sent to the client.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ServerEvent {
+    UserCreated {
+        version: u8,
+        #[serde(flatten)]
+        payload: UserCreatedResponse,
+    },
+    DisplayNameRejected {
+        version: u8,
+        #[serde(flatten)]
+        payload: InvalidDisplayNameResponse,
+    },
+    /// Sent when an inbound frame names a command tag this server doesn't
+    /// recognise, so older/newer clients get a typed response instead of a
+    /// blanket policy close.
+    UnknownCommand {
+        version: u8,
+        code: String,
+        message: String,
+    },
+}
+
+impl ServerEvent {
+    pub fn user_created(payload: UserCreatedResponse) -> Self {
+        Self::UserCreated {
+            version: PROTOCOL_VERSION,
+            payload,
+        }
+    }
+
+    pub fn display_name_rejected(payload: InvalidDisplayNameResponse) -> Self {
+        Self::DisplayNameRejected {
+            version: PROTOCOL_VERSION,
+            payload,
+        }
+    }
+
+    pub fn unknown_command(command_type: &str) -> Self {
+        Self::UnknownCommand {
+            version: PROTOCOL_VERSION,
+            code: "unknown_command".to_owned(),
+            message: format!("unrecognised command type '{command_type}'"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,4 +195,48 @@ mod tests {
         let response: InvalidDisplayNameResponse = event.into();
         assert_json_snapshot!(response);
     }
+
+    #[rstest]
+    fn deserialises_submit_display_name_command() {
+        let payload = serde_json::json!({
+            "type": "submitDisplayName",
+            "traceId": Uuid::nil(),
+            "displayName": "Alice",
+        });
+        let command: ClientCommand =
+            serde_json::from_value(payload).expect("valid command deserialises");
+        let ClientCommand::SubmitDisplayName {
+            trace_id,
+            display_name,
+        } = command;
+        assert_eq!(trace_id, Uuid::nil());
+        assert_eq!(display_name, "Alice");
+    }
+
+    #[rstest]
+    fn rejects_unrecognised_command_tag() {
+        let payload = serde_json::json!({"type": "launchRocket"});
+        assert!(serde_json::from_value::<ClientCommand>(payload).is_err());
+    }
+
+    #[rstest]
+    fn serialises_user_created_server_event() {
+        let user = User::new(
+            UserId::new("3fa85f64-5717-4562-b3fc-2c963f66afa6")
+                .expect("static test UUID must be valid"),
+            DisplayName::new("Alice").expect("static test display name must be valid"),
+        );
+        let event = UserCreatedEvent {
+            trace_id: TraceId::from_uuid(Uuid::nil()),
+            user,
+        };
+        let server_event = ServerEvent::user_created(event.into());
+        assert_json_snapshot!(server_event);
+    }
+
+    #[rstest]
+    fn serialises_unknown_command_event() {
+        let server_event = ServerEvent::unknown_command("launchRocket");
+        assert_json_snapshot!(server_event);
+    }
 }
@@ -2,28 +2,47 @@
 //!
 //! Responsibilities:
 //! - validate upgrade requests (origin allow-list)
+//! - gate the upgrade on the authenticated session cookie
 //! - initialise the per-connection WebSocket actor
+//! - register each connection so it can be drained on server shutdown
+//! - negotiate optional deflate compression (a private extension, see
+//!   [`compression`])
 //! - keep WebSocket-specific concerns at the edge of the system
 
 use actix_web::web::{self, Payload};
 use actix_web::{
     get,
-    http::header::{HeaderValue, ORIGIN},
+    http::header::{HeaderValue, ORIGIN, SEC_WEBSOCKET_EXTENSIONS},
     HttpRequest, HttpResponse,
 };
-use actix_web_actors::ws;
+use actix_ws::{CloseCode, CloseReason};
 use tracing::{error, warn};
 use url::Url;
 
+use crate::inbound::http::session::SessionContext;
+use crate::inbound::ws::compression::CompressionNegotiation;
+use crate::inbound::ws::origin::OriginPolicy;
+
 mod session;
 
+pub mod compression;
 pub mod messages;
+pub mod origin;
+pub mod registry;
 pub mod state;
 
 /// Handle WebSocket upgrade for the `/ws` endpoint.
+///
+/// The session cookie is validated against the same session store the HTTP
+/// `users` endpoints use (see [`SessionContext`]). Connections without a
+/// valid session complete the WebSocket handshake but are immediately closed
+/// with [`CloseCode::Policy`] before the per-connection actor starts, so the
+/// client receives a protocol-level reason rather than a bare dropped
+/// connection.
 #[get("/ws")]
 pub async fn ws_entry(
     state: web::Data<state::WsState>,
+    auth: SessionContext,
     req: HttpRequest,
     stream: Payload,
 ) -> actix_web::Result<HttpResponse> {
@@ -37,16 +56,50 @@ pub async fn ws_entry(
         return Err(actix_web::error::ErrorBadRequest("Invalid Origin header"));
     }
 
-    validate_origin(origin_header)?;
+    validate_origin(origin_header, state.origin_policy())?;
+
+    let user_id = auth.user_id()?;
+
+    let compression = CompressionNegotiation::negotiate(
+        state.compression_enabled(),
+        req.headers().get(SEC_WEBSOCKET_EXTENSIONS),
+    );
+
+    let (mut response, mut ws_session, msg_stream) = actix_ws::handle(&req, stream)?;
+
+    if let Some(negotiated) = compression {
+        response
+            .headers_mut()
+            .insert(SEC_WEBSOCKET_EXTENSIONS, negotiated.response_header());
+    }
+
+    let Some(user_id) = user_id else {
+        warn!("Rejected WS upgrade: no authenticated session");
+        actix_web::rt::spawn(async move {
+            let reason = CloseReason {
+                code: CloseCode::Policy,
+                description: Some("authentication required".to_owned()),
+            };
+            if let Err(error) = ws_session.close(Some(reason)).await {
+                warn!(error = %error, "Failed to close unauthenticated WebSocket session");
+            }
+        });
+        return Ok(response);
+    };
 
-    let actor = session::WsSession::new(state.onboarding.clone());
-    ws::start(actor, &req, stream).map_err(|error| {
-        error!(error = %error, "WebSocket upgrade failed");
-        actix_web::error::ErrorInternalServerError("WebSocket upgrade failed")
-    })
+    let drain = state.registry().subscribe();
+    actix_web::rt::spawn(session::handle_ws_session(
+        state.onboarding.clone(),
+        user_id,
+        ws_session,
+        msg_stream,
+        drain,
+        compression,
+    ));
+    Ok(response)
 }
 
-fn validate_origin(origin_header: &HeaderValue) -> actix_web::Result<()> {
+fn validate_origin(origin_header: &HeaderValue, policy: &OriginPolicy) -> actix_web::Result<()> {
     let origin_value = match origin_header.to_str() {
         Ok(value) => value,
         Err(error) => {
@@ -60,7 +113,7 @@ fn validate_origin(origin_header: &HeaderValue) -> actix_web::Result<()> {
         actix_web::error::ErrorBadRequest("Invalid Origin header")
     })?;
 
-    if is_allowed_origin(&origin) {
+    if policy.is_allowed(&origin) {
         Ok(())
     } else {
         warn!(
@@ -71,30 +124,6 @@ fn validate_origin(origin_header: &HeaderValue) -> actix_web::Result<()> {
     }
 }
 
-const PRIMARY_HOST: &str = "yourdomain.example";
-const LOCALHOST: &str = "localhost";
-const ALLOWED_SUBDOMAIN_SUFFIX: &str = ".yourdomain.example";
-
-/// Returns true when a parsed Origin belongs to the static allow-list.
-///
-/// The allow-list currently accepts HTTPS requests from the production root
-/// domain and any of its subdomains, and HTTP requests from localhost with a
-/// non-zero explicit port. Once configuration is available this should move
-/// into a runtime-controlled allow-list.
-fn is_allowed_origin(origin: &Url) -> bool {
-    let host = match origin.host_str() {
-        Some(value) => value,
-        None => return false,
-    };
-
-    match origin.scheme() {
-        "http" if host == LOCALHOST => matches!(origin.port(), Some(port) if port != 0),
-        "https" if host == PRIMARY_HOST => true,
-        "https" if host.strip_suffix(ALLOWED_SUBDOMAIN_SUFFIX).is_some() => true,
-        _ => false,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,13 +134,17 @@ mod tests {
         HeaderValue::from_str(value).expect("valid header value")
     }
 
+    fn policy() -> OriginPolicy {
+        OriginPolicy::default_allow_list()
+    }
+
     #[rstest]
     #[case("http://localhost:3000")]
     #[case("https://yourdomain.example")]
     #[case("https://chat.yourdomain.example")]
     fn accepts_configured_origins(#[case] origin: &str) {
         let header = header(origin);
-        assert!(validate_origin(&header).is_ok());
+        assert!(validate_origin(&header, &policy()).is_ok());
     }
 
     #[rstest]
@@ -120,7 +153,7 @@ mod tests {
     #[case("wss://yourdomain.example")]
     fn rejects_disallowed_origins(#[case] origin: &str) {
         let header = header(origin);
-        let error = validate_origin(&header).expect_err("origin should be rejected");
+        let error = validate_origin(&header, &policy()).expect_err("origin should be rejected");
         assert_eq!(
             error.as_response_error().status_code(),
             StatusCode::FORBIDDEN
@@ -130,7 +163,7 @@ mod tests {
     #[test]
     fn rejects_non_utf8_origin_header() {
         let header = HeaderValue::from_bytes(&[0x80]).expect("opaque header value");
-        let error = validate_origin(&header).expect_err("origin should be rejected");
+        let error = validate_origin(&header, &policy()).expect_err("origin should be rejected");
         assert_eq!(
             error.as_response_error().status_code(),
             StatusCode::BAD_REQUEST
@@ -140,7 +173,7 @@ mod tests {
     #[test]
     fn rejects_unparsable_origin_header() {
         let header = HeaderValue::from_static("not a url");
-        let error = validate_origin(&header).expect_err("origin should be rejected");
+        let error = validate_origin(&header, &policy()).expect_err("origin should be rejected");
         assert_eq!(
             error.as_response_error().status_code(),
             StatusCode::BAD_REQUEST
@@ -157,6 +190,6 @@ mod tests {
     #[case("wss://yourdomain.example", false)]
     fn evaluates_allow_list(#[case] origin: &str, #[case] expected: bool) {
         let parsed = Url::parse(origin).expect("url should parse");
-        assert_eq!(is_allowed_origin(&parsed), expected);
+        assert_eq!(policy().is_allowed(&parsed), expected);
     }
 }
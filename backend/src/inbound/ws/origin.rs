@@ -0,0 +1,140 @@
+//! Runtime-configurable Origin allow-list for the WebSocket upgrade.
+//!
+//! Replaces the previous hard-coded `PRIMARY_HOST`/`LOCALHOST` constants with
+//! a policy object built from configuration, so operators can add preview
+//! environments or additional deployments without recompiling.
+
+use globset::{Glob, GlobMatcher};
+use url::Url;
+
+/// One error building an [`OriginPolicy`] from configuration.
+#[derive(Debug, thiserror::Error)]
+pub enum OriginPolicyError {
+    #[error("invalid host pattern {pattern:?}: {source}")]
+    InvalidHostPattern {
+        pattern: String,
+        #[source]
+        source: globset::Error,
+    },
+}
+
+/// Constraint an allow-list entry places on the Origin's port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortRule {
+    /// Any port, including an implicit default, is accepted.
+    Any,
+    /// Only this exact port is accepted.
+    Exact(u16),
+    /// Any explicit, non-zero port is accepted (no implicit default).
+    NonZero,
+}
+
+impl PortRule {
+    fn matches(self, port: Option<u16>) -> bool {
+        match self {
+            PortRule::Any => true,
+            PortRule::Exact(expected) => port == Some(expected),
+            PortRule::NonZero => matches!(port, Some(port) if port != 0),
+        }
+    }
+}
+
+/// One allow-list entry: a required scheme, a host pattern, and a port rule.
+///
+/// The host pattern is compiled once via [`globset`] so `*`, `?`, and `[...]`
+/// wildcards behave the same way a shell glob would (for example
+/// `*.yourdomain.example` matches any subdomain).
+struct AllowListEntry {
+    scheme: &'static str,
+    host: GlobMatcher,
+    port: PortRule,
+}
+
+impl AllowListEntry {
+    fn new(scheme: &'static str, host_pattern: &str, port: PortRule) -> Result<Self, OriginPolicyError> {
+        let host = Glob::new(host_pattern)
+            .map_err(|source| OriginPolicyError::InvalidHostPattern {
+                pattern: host_pattern.to_owned(),
+                source,
+            })?
+            .compile_matcher();
+        Ok(Self { scheme, host, port })
+    }
+
+    fn matches(&self, origin: &Url) -> bool {
+        let Some(host) = origin.host_str() else {
+            return false;
+        };
+
+        origin.scheme() == self.scheme && self.host.is_match(host) && self.port.matches(origin.port())
+    }
+}
+
+/// Compiled set of allow-list entries checked against an upgrade's Origin header.
+pub struct OriginPolicy {
+    entries: Vec<AllowListEntry>,
+}
+
+impl OriginPolicy {
+    /// Compile a policy from `(scheme, host_pattern, port_rule)` triples.
+    pub fn new(
+        entries: impl IntoIterator<Item = (&'static str, &'static str, PortRule)>,
+    ) -> Result<Self, OriginPolicyError> {
+        let entries = entries
+            .into_iter()
+            .map(|(scheme, host_pattern, port)| AllowListEntry::new(scheme, host_pattern, port))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { entries })
+    }
+
+    /// The production allow-list: the primary domain, any of its subdomains
+    /// over HTTPS, and `http://localhost` with a non-zero explicit port for
+    /// local development.
+    ///
+    /// # Panics
+    ///
+    /// Never panics: the patterns above are compile-time constants known to
+    /// be valid globs.
+    #[must_use]
+    pub fn default_allow_list() -> Self {
+        Self::new([
+            ("http", "localhost", PortRule::NonZero),
+            ("https", "yourdomain.example", PortRule::Any),
+            ("https", "*.yourdomain.example", PortRule::Any),
+        ])
+        .expect("default allow-list patterns are valid globs")
+    }
+
+    /// Returns true when `origin` matches any configured allow-list entry.
+    #[must_use]
+    pub fn is_allowed(&self, origin: &Url) -> bool {
+        self.entries.iter().any(|entry| entry.matches(origin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    fn accepts_custom_exact_port() {
+        let policy = OriginPolicy::new([("https", "preview.example", PortRule::Exact(8443))])
+            .expect("valid policy");
+
+        let allowed = Url::parse("https://preview.example:8443").expect("url should parse");
+        let disallowed = Url::parse("https://preview.example:9000").expect("url should parse");
+
+        assert!(policy.is_allowed(&allowed));
+        assert!(!policy.is_allowed(&disallowed));
+    }
+
+    #[test]
+    fn rejects_invalid_host_pattern() {
+        let result = OriginPolicy::new([("https", "[unterminated", PortRule::Any)]);
+        assert!(matches!(
+            result,
+            Err(OriginPolicyError::InvalidHostPattern { .. })
+        ));
+    }
+}
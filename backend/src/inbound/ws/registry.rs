@@ -0,0 +1,46 @@
+//! Registry coordinating a graceful, server-wide WebSocket drain.
+//!
+//! Each `WsSession` subscribes when it starts handling a connection; the
+//! subscription is dropped implicitly when the session loop returns, so there
+//! is nothing to unregister explicitly. Calling [`ConnectionRegistry::drain`]
+//! broadcasts a shutdown signal to every live connection so it can close with
+//! [`CloseCode::Away`](actix_ws::CloseCode::Away) instead of being dropped
+//! mid-deploy.
+
+use tokio::sync::broadcast;
+
+/// Bounded so a burst of drain calls cannot grow the channel unboundedly;
+/// in practice `drain` is called at most once per shutdown.
+const DRAIN_CHANNEL_CAPACITY: usize = 16;
+
+/// Broadcast channel coordinating a server-wide WebSocket drain.
+#[derive(Clone)]
+pub struct ConnectionRegistry {
+    sender: broadcast::Sender<()>,
+}
+
+impl ConnectionRegistry {
+    /// Create a registry with no connections subscribed yet.
+    pub fn new() -> Self {
+        let (sender, _receiver) = broadcast::channel(DRAIN_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Register a connection, returning a receiver that fires once the
+    /// registry is drained.
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.sender.subscribe()
+    }
+
+    /// Signal every registered connection to close gracefully.
+    pub fn drain(&self) {
+        // No receivers means no open connections; nothing to notify.
+        let _ = self.sender.send(());
+    }
+}
+
+impl Default for ConnectionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
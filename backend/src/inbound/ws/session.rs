@@ -5,19 +5,26 @@
 //! WebSocket contract pings every 5s and considers a connection idle after
 //! 10s without client traffic. Tests shorten these intervals to speed up
 //! feedback; adjust the constants below if SLAs change so clients and
-//! intermediaries stay aligned.
+//! intermediaries stay aligned. Each session also subscribes to the shared
+//! [`ConnectionRegistry`](crate::inbound::ws::registry::ConnectionRegistry) so
+//! a server-wide drain can close it gracefully instead of dropping it. A
+//! token-bucket limiter guards inbound text/binary frames so a flooding
+//! client pays with a policy close rather than burning CPU on every frame.
 
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crate::domain::ports::UserOnboarding;
-use crate::domain::{TraceId, UserEvent};
+use crate::domain::{TraceId, UserEvent, UserId};
+use crate::inbound::ws::compression::{self, CompressionNegotiation, COMPRESSION_THRESHOLD_BYTES};
 use crate::inbound::ws::messages::{
-    DisplayNameRequest, InvalidDisplayNameResponse, UserCreatedResponse,
+    ClientCommand, InvalidDisplayNameResponse, ServerEvent, UserCreatedResponse,
 };
 use actix_ws::{CloseCode, CloseReason, Closed, Message, MessageStream, ProtocolError, Session};
+use tokio::sync::broadcast;
 use tokio::time;
-use tracing::warn;
+use tracing::{info, warn};
+use uuid::Uuid;
 
 /// Time between heartbeats to the client (5s in production, shorter in tests).
 #[cfg(not(test))]
@@ -31,12 +38,29 @@ const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
 #[cfg(test)]
 const CLIENT_TIMEOUT: Duration = Duration::from_millis(100);
 
+/// Token-bucket capacity for inbound frames (burst size before throttling kicks in).
+#[cfg(not(test))]
+const RATE_LIMIT_CAPACITY: f64 = 20.0;
+#[cfg(test)]
+const RATE_LIMIT_CAPACITY: f64 = 3.0;
+
+/// Token-bucket refill rate, in tokens per second.
+#[cfg(not(test))]
+const RATE_LIMIT_REFILL_PER_SEC: f64 = 10.0;
+#[cfg(test)]
+const RATE_LIMIT_REFILL_PER_SEC: f64 = 1.0;
+
 pub(super) async fn handle_ws_session(
     onboarding: Arc<dyn UserOnboarding>,
+    user_id: UserId,
     session: Session,
     stream: MessageStream,
+    drain: broadcast::Receiver<()>,
+    compression: Option<CompressionNegotiation>,
 ) {
-    WsSession::new(onboarding).run(session, stream).await;
+    WsSession::new(onboarding, user_id, compression)
+        .run(session, stream, drain)
+        .await;
 }
 
 enum SessionError {
@@ -46,6 +70,8 @@ enum SessionError {
     Protocol(ProtocolError),
     InvalidPayload,
     Network(Closed),
+    Drained,
+    RateLimited,
 }
 
 enum CloseAction {
@@ -55,14 +81,56 @@ enum CloseAction {
 
 struct WsSession {
     onboarding: Arc<dyn UserOnboarding>,
+    /// Principal resolved from the session cookie at handshake time.
+    user_id: UserId,
+    /// Tokens currently available in the inbound-frame rate limiter.
+    tokens: f64,
+    /// When the rate limiter's tokens were last topped up.
+    last_refill: Instant,
+    /// Compression-extension parameters negotiated at handshake time, if any.
+    compression: Option<CompressionNegotiation>,
 }
 
 impl WsSession {
-    fn new(onboarding: Arc<dyn UserOnboarding>) -> Self {
-        Self { onboarding }
+    fn new(
+        onboarding: Arc<dyn UserOnboarding>,
+        user_id: UserId,
+        compression: Option<CompressionNegotiation>,
+    ) -> Self {
+        Self {
+            onboarding,
+            user_id,
+            tokens: RATE_LIMIT_CAPACITY,
+            last_refill: Instant::now(),
+            compression,
+        }
+    }
+
+    /// Refill the token bucket for elapsed time, then take one token.
+    ///
+    /// Returns `false` (without consuming a token) when the bucket is empty,
+    /// signalling that the client has exceeded the rate limit; the caller
+    /// then closes the connection with a policy close.
+    fn take_rate_limit_token(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * RATE_LIMIT_REFILL_PER_SEC).min(RATE_LIMIT_CAPACITY);
+        self.last_refill = now;
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+
+        self.tokens -= 1.0;
+        true
     }
 
-    async fn run(&self, mut session: Session, mut stream: MessageStream) {
+    async fn run(
+        &mut self,
+        mut session: Session,
+        mut stream: MessageStream,
+        mut drain: broadcast::Receiver<()>,
+    ) {
         let mut last_heartbeat = Instant::now();
         let mut heartbeat = time::interval(HEARTBEAT_INTERVAL);
 
@@ -75,6 +143,9 @@ impl WsSession {
                     self.handle_stream_message(&mut session, &mut last_heartbeat, message)
                         .await
                 }
+                _ = drain.recv() => {
+                    self.handle_drain_signal()
+                }
             };
 
             if let Err(error) = result {
@@ -86,6 +157,10 @@ impl WsSession {
         }
     }
 
+    fn handle_drain_signal(&self) -> Result<(), SessionError> {
+        Err(SessionError::Drained)
+    }
+
     async fn handle_heartbeat_tick(
         &self,
         session: &mut Session,
@@ -99,7 +174,7 @@ impl WsSession {
     }
 
     async fn handle_stream_message(
-        &self,
+        &mut self,
         session: &mut Session,
         last_heartbeat: &mut Instant,
         message: Option<Result<Message, ProtocolError>>,
@@ -115,7 +190,7 @@ impl WsSession {
     }
 
     async fn handle_message(
-        &self,
+        &mut self,
         session: &mut Session,
         last_heartbeat: &mut Instant,
         message: Message,
@@ -131,9 +206,19 @@ impl WsSession {
             }
             Message::Text(text) => {
                 *last_heartbeat = Instant::now();
+                if !self.take_rate_limit_token() {
+                    return Err(SessionError::RateLimited);
+                }
                 self.handle_text_message(session, text.as_ref()).await
             }
-            Message::Pong(_) | Message::Binary(_) | Message::Continuation(_) | Message::Nop => {
+            Message::Binary(payload) => {
+                *last_heartbeat = Instant::now();
+                if !self.take_rate_limit_token() {
+                    return Err(SessionError::RateLimited);
+                }
+                self.handle_binary_message(session, payload.as_ref()).await
+            }
+            Message::Pong(_) | Message::Continuation(_) | Message::Nop => {
                 *last_heartbeat = Instant::now();
                 Ok(())
             }
@@ -141,29 +226,89 @@ impl WsSession {
         }
     }
 
+    /// Handle an inbound binary frame.
+    ///
+    /// When the compression extension is negotiated, binary frames carry a
+    /// deflated JSON command (see the module docs in
+    /// [`compression`](crate::inbound::ws::compression)); without it, binary
+    /// frames aren't part of the protocol and are ignored like before.
+    async fn handle_binary_message(
+        &self,
+        session: &mut Session,
+        payload: &[u8],
+    ) -> Result<(), SessionError> {
+        if self.compression.is_none() {
+            return Ok(());
+        }
+
+        let decompressed = match compression::decompress(payload) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                warn!(error = %error, "Rejected undecodable compressed WebSocket payload");
+                return Err(SessionError::InvalidPayload);
+            }
+        };
+        let text = match std::str::from_utf8(&decompressed) {
+            Ok(text) => text,
+            Err(error) => {
+                warn!(error = %error, "Decompressed WebSocket payload was not valid UTF-8");
+                return Err(SessionError::InvalidPayload);
+            }
+        };
+
+        self.handle_text_message(session, text).await
+    }
+
     async fn handle_text_message(
         &self,
         session: &mut Session,
         text: &str,
     ) -> Result<(), SessionError> {
-        let request = match serde_json::from_str::<DisplayNameRequest>(text) {
-            Ok(request) => request,
+        let value = match serde_json::from_str::<serde_json::Value>(text) {
+            Ok(value) => value,
             Err(error) => {
                 warn!(error = %error, "Rejected malformed WebSocket payload");
                 return Err(SessionError::InvalidPayload);
             }
         };
 
-        let event = self.handle_display_name_request(request);
-        self.handle_user_event(session, event)
-            .await
-            .map_err(SessionError::Network)
+        match serde_json::from_value::<ClientCommand>(value.clone()) {
+            Ok(command) => self.handle_client_command(session, command).await,
+            Err(_) => {
+                let command_type = value
+                    .get("type")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or("unknown");
+                warn!(command_type, "Rejected unrecognised WebSocket command");
+                self.send_json(session, &ServerEvent::unknown_command(command_type))
+                    .await
+                    .map_err(SessionError::Network)
+            }
+        }
+    }
+
+    async fn handle_client_command(
+        &self,
+        session: &mut Session,
+        command: ClientCommand,
+    ) -> Result<(), SessionError> {
+        match command {
+            ClientCommand::SubmitDisplayName {
+                trace_id,
+                display_name,
+            } => {
+                let event = self.handle_display_name_request(trace_id, display_name);
+                self.handle_user_event(session, event)
+                    .await
+                    .map_err(SessionError::Network)
+            }
+        }
     }
 
-    fn handle_display_name_request(&self, request: DisplayNameRequest) -> UserEvent {
-        let trace_id = TraceId::from_uuid(request.trace_id);
+    fn handle_display_name_request(&self, trace_id: Uuid, display_name: String) -> UserEvent {
+        let trace_id = TraceId::from_uuid(trace_id);
         // `register` must remain CPU-bound; any I/O work should be offloaded to other tasks.
-        self.onboarding.register(trace_id, request.display_name)
+        self.onboarding.register(trace_id, display_name)
     }
 
     async fn handle_user_event(
@@ -173,12 +318,17 @@ impl WsSession {
     ) -> Result<(), Closed> {
         match event {
             UserEvent::UserCreated(event) => {
-                let response: UserCreatedResponse = event.into();
-                self.send_json(session, &response).await
+                // Attribute the event to the session's authenticated principal
+                // rather than the fresh random id the onboarding service mints.
+                let mut payload: UserCreatedResponse = event.into();
+                payload.id = self.user_id.to_string();
+                self.send_json(session, &ServerEvent::user_created(payload))
+                    .await
             }
             UserEvent::DisplayNameRejected(event) => {
-                let response: InvalidDisplayNameResponse = event.into();
-                self.send_json(session, &response).await
+                let payload: InvalidDisplayNameResponse = event.into();
+                self.send_json(session, &ServerEvent::display_name_rejected(payload))
+                    .await
             }
         }
     }
@@ -188,8 +338,8 @@ impl WsSession {
         session: &mut Session,
         payload: &T,
     ) -> Result<(), Closed> {
-        match serde_json::to_string(payload) {
-            Ok(body) => session.text(body).await,
+        let body = match serde_json::to_string(payload) {
+            Ok(body) => body,
             Err(error) => {
                 // In debug builds fail fast so schema drift is fixed; in release we log and keep the connection alive.
                 if cfg!(debug_assertions) {
@@ -197,9 +347,20 @@ impl WsSession {
                 } else {
                     warn!(error = %error, "Failed to serialize WebSocket payload");
                 }
-                Ok(())
+                return Ok(());
+            }
+        };
+
+        if self.compression.is_some() && body.len() >= COMPRESSION_THRESHOLD_BYTES {
+            match compression::compress(body.as_bytes()) {
+                Ok(compressed) => return session.binary(compressed).await,
+                Err(error) => {
+                    warn!(error = %error, "Failed to compress WebSocket payload; sending uncompressed");
+                }
             }
         }
+
+        session.text(body).await
     }
 
     fn log_shutdown_reason(&self, error: &SessionError) {
@@ -213,6 +374,12 @@ impl WsSession {
             SessionError::Network(error) => {
                 warn!(error = %error, "WebSocket send failed; closing connection");
             }
+            SessionError::Drained => {
+                info!("Draining WebSocket connection for server shutdown");
+            }
+            SessionError::RateLimited => {
+                warn!("WebSocket client exceeded inbound frame rate limit; closing connection");
+            }
             SessionError::InvalidPayload
             | SessionError::ClientClosed(_)
             | SessionError::StreamClosed => {}
@@ -233,6 +400,14 @@ impl WsSession {
                 code: CloseCode::Policy,
                 description: Some("invalid payload".to_owned()),
             })),
+            SessionError::Drained => CloseAction::Close(Some(CloseReason {
+                code: CloseCode::Away,
+                description: Some("server restarting".to_owned()),
+            })),
+            SessionError::RateLimited => CloseAction::Close(Some(CloseReason {
+                code: CloseCode::Policy,
+                description: Some("rate limit exceeded".to_owned()),
+            })),
             SessionError::ClientClosed(reason) => CloseAction::Close(reason.clone()),
             SessionError::StreamClosed | SessionError::Network(_) => CloseAction::None,
         }
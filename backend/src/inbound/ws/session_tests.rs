@@ -2,9 +2,11 @@
 
 use super::*;
 use crate::domain::UserOnboardingService;
+use crate::inbound::http::session::SessionContext;
+use crate::inbound::http::test_utils::test_session_middleware;
 use crate::inbound::ws;
 use crate::inbound::ws::state::WsState;
-use actix_web::{App, HttpServer, dev::Server, dev::ServerHandle, http::header};
+use actix_web::{App, HttpResponse, HttpServer, dev::Server, dev::ServerHandle, http::header, web};
 use awc::{BoxedSocket, ws::Codec, ws::Frame, ws::Message};
 use futures_util::{SinkExt, StreamExt};
 use rstest::{fixture, rstest};
@@ -12,35 +14,89 @@ use serde_json::Value;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::inbound::ws::compression;
+
+/// Fixture principal persisted by the `/test-login` route below.
+const TEST_USER_ID: &str = "3fa85f64-5717-4562-b3fc-2c963f66afa6";
+
+async fn test_login(session: SessionContext) -> HttpResponse {
+    let user_id = UserId::new(TEST_USER_ID).expect("fixture user id is valid");
+    session.persist_user(&user_id).expect("persist session");
+    HttpResponse::Ok().finish()
+}
+
 #[fixture]
-async fn start_ws_server() -> (String, Server) {
+async fn start_ws_server() -> (String, Server, WsState) {
     let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind test listener");
     let addr = listener.local_addr().expect("listener addr");
     let ws_state = WsState::new(Arc::new(UserOnboardingService));
+    let state_for_app = ws_state.clone();
     let server = HttpServer::new(move || {
         App::new()
-            .app_data(actix_web::web::Data::new(ws_state.clone()))
+            .app_data(actix_web::web::Data::new(state_for_app.clone()))
+            .wrap(test_session_middleware())
             .service(ws::ws_entry)
+            .route("/test-login", web::get().to(test_login))
     })
     .listen(listener)
     .expect("bind test server")
     .disable_signals()
     .run();
     let url = format!("http://{addr}");
-    (url, server)
+    (url, server, ws_state)
+}
+
+#[fixture]
+async fn start_compressed_ws_server() -> (String, Server, WsState) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind test listener");
+    let addr = listener.local_addr().expect("listener addr");
+    let ws_state = WsState::new(Arc::new(UserOnboardingService)).with_compression(true);
+    let state_for_app = ws_state.clone();
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(actix_web::web::Data::new(state_for_app.clone()))
+            .wrap(test_session_middleware())
+            .service(ws::ws_entry)
+            .route("/test-login", web::get().to(test_login))
+    })
+    .listen(listener)
+    .expect("bind test server")
+    .disable_signals()
+    .run();
+    let url = format!("http://{addr}");
+    (url, server, ws_state)
+}
+
+/// Log in against `/test-login` and return the resulting session cookie.
+async fn authenticated_session_cookie(url: &str) -> actix_web::cookie::Cookie<'static> {
+    let response = awc::Client::default()
+        .get(format!("{url}/test-login"))
+        .send()
+        .await
+        .expect("login request succeeds");
+    response
+        .cookies()
+        .expect("cookies present")
+        .into_iter()
+        .find(|cookie| cookie.name() == "session")
+        .expect("session cookie set")
+        .into_owned()
 }
 
 #[fixture]
 async fn ws_client(
-    #[future] start_ws_server: (String, Server),
+    #[future] start_ws_server: (String, Server, WsState),
 ) -> (actix_codec::Framed<BoxedSocket, Codec>, ServerHandle) {
-    let (url, server) = start_ws_server.await;
+    let (url, server, _ws_state) = start_ws_server.await;
     let handle = server.handle();
     actix_web::rt::spawn(server);
 
+    let cookie = authenticated_session_cookie(&url).await;
+
     let (_resp, socket) = awc::Client::default()
         .ws(format!("{url}/ws"))
         .set_header(header::ORIGIN, "http://localhost:3000")
+        .set_header(header::COOKIE, format!("session={}", cookie.value()))
         .connect()
         .await
         .expect("websocket connect");
@@ -50,6 +106,7 @@ async fn ws_client(
 
 fn handshake_request_payload(name: &str) -> String {
     serde_json::json!({
+        "type": "submitDisplayName",
         "traceId": Uuid::nil(),
         "displayName": name
     })
@@ -84,13 +141,40 @@ async fn sends_user_created_event_for_valid_payload(
         value.get("displayName").and_then(Value::as_str),
         Some("Bob")
     );
-    assert!(value.get("id").is_some(), "user id present");
+    assert_eq!(value.get("id").and_then(Value::as_str), Some(TEST_USER_ID));
     assert_eq!(
         value.get("traceId").and_then(Value::as_str),
         Some(Uuid::nil().to_string().as_str())
     );
 }
 
+#[rstest]
+#[actix_rt::test]
+async fn rejects_connection_without_session(
+    #[future] start_ws_server: (String, Server, WsState),
+) {
+    let (url, server, _ws_state) = start_ws_server.await;
+    let handle = server.handle();
+    actix_web::rt::spawn(server);
+
+    let (_resp, mut socket) = awc::Client::default()
+        .ws(format!("{url}/ws"))
+        .set_header(header::ORIGIN, "http://localhost:3000")
+        .connect()
+        .await
+        .expect("websocket handshake completes even without a session");
+
+    let frame = socket.next().await.expect("response frame").expect("frame");
+    match frame {
+        Frame::Close(reason) => {
+            assert_eq!(reason.expect("reason").code, CloseCode::Policy);
+        }
+        other => panic!("expected close frame, got {other:?}"),
+    }
+
+    handle.stop(true).await;
+}
+
 #[rstest]
 #[actix_rt::test]
 async fn sends_rejection_for_invalid_payload(
@@ -117,6 +201,31 @@ async fn sends_rejection_for_invalid_payload(
     );
 }
 
+#[rstest]
+#[actix_rt::test]
+async fn sends_error_event_for_unrecognised_command(
+    #[future] ws_client: (actix_codec::Framed<BoxedSocket, Codec>, ServerHandle),
+) {
+    let (mut socket, _server): (actix_codec::Framed<_, _>, _) = ws_client.await;
+    socket
+        .send(Message::Text(
+            serde_json::json!({"type": "launchRocket"}).to_string().into(),
+        ))
+        .await
+        .expect("send text");
+
+    let text = next_text_frame(&mut socket).await;
+    let value: Value = serde_json::from_slice(&text).expect("json");
+    assert_eq!(
+        value.get("type").and_then(Value::as_str),
+        Some("unknownCommand")
+    );
+    assert_eq!(
+        value.get("code").and_then(Value::as_str),
+        Some("unknown_command")
+    );
+}
+
 #[rstest]
 #[actix_rt::test]
 async fn closes_on_malformed_json(
@@ -170,3 +279,146 @@ async fn closes_after_timeout_without_client_messages(
     assert_eq!(reason.code, CloseCode::Normal);
     assert_eq!(reason.description.as_deref(), Some("heartbeat timeout"));
 }
+
+#[rstest]
+#[actix_rt::test]
+async fn closes_with_policy_when_rate_limit_exceeded(
+    #[future] ws_client: (actix_codec::Framed<BoxedSocket, Codec>, ServerHandle),
+) {
+    let (mut socket, _server): (actix_codec::Framed<_, _>, _) = ws_client.await;
+
+    // RATE_LIMIT_CAPACITY is 3 tokens under `#[cfg(test)]`; burst one frame
+    // past the bucket so the next send is dropped with a policy close.
+    for name in ["Bob", "Carol", "Dave", "Eve"] {
+        socket
+            .send(Message::Text(handshake_request_payload(name).into()))
+            .await
+            .expect("send text");
+    }
+
+    for _ in 0..3 {
+        let _ = next_text_frame(&mut socket).await;
+    }
+
+    let frame = socket.next().await.expect("response frame").expect("frame");
+    match frame {
+        Frame::Close(reason) => {
+            let reason = reason.expect("reason");
+            assert_eq!(reason.code, CloseCode::Policy);
+            assert_eq!(reason.description.as_deref(), Some("rate limit exceeded"));
+        }
+        other => panic!("expected close frame, got {other:?}"),
+    }
+}
+
+#[rstest]
+#[actix_rt::test]
+async fn drains_open_connections_with_away_close(
+    #[future] start_ws_server: (String, Server, WsState),
+) {
+    let (url, server, ws_state) = start_ws_server.await;
+    let handle = server.handle();
+    actix_web::rt::spawn(server);
+
+    let cookie_one = authenticated_session_cookie(&url).await;
+    let cookie_two = authenticated_session_cookie(&url).await;
+
+    let (_resp, mut socket_one) = awc::Client::default()
+        .ws(format!("{url}/ws"))
+        .set_header(header::ORIGIN, "http://localhost:3000")
+        .set_header(header::COOKIE, format!("session={}", cookie_one.value()))
+        .connect()
+        .await
+        .expect("websocket connect");
+    let (_resp, mut socket_two) = awc::Client::default()
+        .ws(format!("{url}/ws"))
+        .set_header(header::ORIGIN, "http://localhost:3000")
+        .set_header(header::COOKIE, format!("session={}", cookie_two.value()))
+        .connect()
+        .await
+        .expect("websocket connect");
+
+    ws_state.registry().drain();
+
+    for socket in [&mut socket_one, &mut socket_two] {
+        let frame = socket.next().await.expect("response frame").expect("frame");
+        match frame {
+            Frame::Close(reason) => {
+                let reason = reason.expect("reason");
+                assert_eq!(reason.code, CloseCode::Away);
+                assert_eq!(reason.description.as_deref(), Some("server restarting"));
+            }
+            other => panic!("expected close frame, got {other:?}"),
+        }
+    }
+
+    handle.stop(true).await;
+}
+
+#[rstest]
+#[actix_rt::test]
+async fn negotiates_compression_extension_when_offered(
+    #[future] start_compressed_ws_server: (String, Server, WsState),
+) {
+    let (url, server, _ws_state) = start_compressed_ws_server.await;
+    let handle = server.handle();
+    actix_web::rt::spawn(server);
+
+    let cookie = authenticated_session_cookie(&url).await;
+
+    let (resp, _socket) = awc::Client::default()
+        .ws(format!("{url}/ws"))
+        .set_header(header::ORIGIN, "http://localhost:3000")
+        .set_header(header::COOKIE, format!("session={}", cookie.value()))
+        .set_header(header::SEC_WEBSOCKET_EXTENSIONS, "x-wildside-deflate")
+        .connect()
+        .await
+        .expect("websocket connect");
+
+    let extensions = resp
+        .headers()
+        .get(header::SEC_WEBSOCKET_EXTENSIONS)
+        .expect("negotiated extension header present")
+        .to_str()
+        .expect("header is ascii");
+    assert!(extensions.contains("x-wildside-deflate"));
+
+    handle.stop(true).await;
+}
+
+#[rstest]
+#[actix_rt::test]
+async fn decodes_compressed_binary_command(
+    #[future] start_compressed_ws_server: (String, Server, WsState),
+) {
+    let (url, server, _ws_state) = start_compressed_ws_server.await;
+    let handle = server.handle();
+    actix_web::rt::spawn(server);
+
+    let cookie = authenticated_session_cookie(&url).await;
+
+    let (_resp, mut socket) = awc::Client::default()
+        .ws(format!("{url}/ws"))
+        .set_header(header::ORIGIN, "http://localhost:3000")
+        .set_header(header::COOKIE, format!("session={}", cookie.value()))
+        .set_header(header::SEC_WEBSOCKET_EXTENSIONS, "x-wildside-deflate")
+        .connect()
+        .await
+        .expect("websocket connect");
+
+    let body = handshake_request_payload("Compressed");
+    let compressed = compression::compress(body.as_bytes()).expect("compress payload");
+    socket
+        .send(Message::Binary(compressed.into()))
+        .await
+        .expect("send binary");
+
+    let text = next_text_frame(&mut socket).await;
+    let value: Value = serde_json::from_slice(&text).expect("json");
+    assert_eq!(
+        value.get("displayName").and_then(Value::as_str),
+        Some("Compressed")
+    );
+
+    handle.stop(true).await;
+}
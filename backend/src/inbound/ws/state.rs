@@ -7,16 +7,67 @@
 use std::sync::Arc;
 
 use crate::domain::ports::UserOnboarding;
+use crate::inbound::ws::origin::OriginPolicy;
+use crate::inbound::ws::registry::ConnectionRegistry;
 
 /// Dependency bundle for WebSocket handlers and actors.
 #[derive(Clone)]
 pub struct WsState {
     pub onboarding: Arc<dyn UserOnboarding>,
+    registry: ConnectionRegistry,
+    compression_enabled: bool,
+    origin_policy: Arc<OriginPolicy>,
 }
 
 impl WsState {
     /// Construct state from explicit port implementations.
+    ///
+    /// Compression-extension negotiation (see
+    /// [`compression`](crate::inbound::ws::compression)) is disabled by
+    /// default; opt in with
+    /// [`WsState::with_compression`]. The Origin allow-list defaults to
+    /// [`OriginPolicy::default_allow_list`]; override with
+    /// [`WsState::with_origin_policy`].
     pub fn new(onboarding: Arc<dyn UserOnboarding>) -> Self {
-        Self { onboarding }
+        Self {
+            onboarding,
+            registry: ConnectionRegistry::new(),
+            compression_enabled: false,
+            origin_policy: Arc::new(OriginPolicy::default_allow_list()),
+        }
+    }
+
+    /// Replace the Origin allow-list used to validate `/ws` upgrades.
+    #[must_use]
+    pub fn with_origin_policy(mut self, origin_policy: OriginPolicy) -> Self {
+        self.origin_policy = Arc::new(origin_policy);
+        self
+    }
+
+    /// The Origin allow-list used to validate `/ws` upgrades.
+    pub fn origin_policy(&self) -> &OriginPolicy {
+        &self.origin_policy
+    }
+
+    /// Toggle whether the `/ws` upgrade negotiates the private deflate
+    /// extension (see [`compression`](crate::inbound::ws::compression)).
+    ///
+    /// Analogous to a feature flag like vaultwarden's `ENABLE_WEBSOCKET`:
+    /// operators can disable compression entirely if a deployment's
+    /// intermediaries mishandle the extension.
+    #[must_use]
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression_enabled = enabled;
+        self
+    }
+
+    /// Registry coordinating a graceful, server-wide WebSocket drain.
+    pub fn registry(&self) -> &ConnectionRegistry {
+        &self.registry
+    }
+
+    /// Whether compression-extension negotiation is enabled for new connections.
+    pub fn compression_enabled(&self) -> bool {
+        self.compression_enabled
     }
 }
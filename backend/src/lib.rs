@@ -11,7 +11,7 @@ pub mod inbound;
 mod middleware;
 pub mod outbound;
 pub use domain::TraceId;
-pub use middleware::Trace;
+pub use middleware::{SecurityHeaders, Trace};
 
 /// Public OpenAPI surface used by Swagger UI and tooling.
 pub use doc::ApiDoc;
@@ -1,8 +1,10 @@
 //! Request middleware.
 //!
 //! Purpose: Define middleware components for request lifecycle concerns such as
-//! tracing and authentication.
+//! tracing, authentication, and shared CORS/security headers.
 
+pub mod security_headers;
 pub mod trace;
 
+pub use security_headers::SecurityHeaders;
 pub use trace::Trace;
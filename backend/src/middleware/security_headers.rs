@@ -0,0 +1,413 @@
+//! Shared CORS and baseline security header middleware.
+//!
+//! Emits a small set of defensive response headers — CORS headers decided by
+//! an [`OriginPolicy`], the same allow-list type the WebSocket upgrade
+//! enforces (see [`crate::inbound::ws`]), plus standard
+//! anti-clickjacking/anti-sniffing headers — on every ordinary response. The
+//! policy instance is supplied when the middleware is built; it is separate
+//! from the one held by the WebSocket state.
+//!
+//! Proxies and CDNs that rewrite or strip headers on a `101 Switching
+//! Protocols` response have been known to interfere with the WebSocket
+//! upgrade, so genuine upgrade handshakes (detected by
+//! [`is_upgrade_request`]) are left untouched rather than decorated with
+//! headers that don't apply to them.
+//!
+//! `OPTIONS` preflight requests (detected by [`is_preflight_request`]) are
+//! short-circuited with a `204 No Content` carrying the CORS response
+//! headers before they ever reach routing, since a preflighted cross-origin
+//! request otherwise has no matching `OPTIONS` route to answer it.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{
+    HeaderMap, HeaderName, HeaderValue, ACCESS_CONTROL_ALLOW_CREDENTIALS,
+    ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+    ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, CONNECTION, ORIGIN, UPGRADE,
+    VARY,
+};
+use actix_web::http::Method;
+use actix_web::{Error, HttpResponse};
+use futures_util::future::{ready, LocalBoxFuture, Ready};
+use url::Url;
+
+use crate::inbound::ws::origin::OriginPolicy;
+
+/// Methods this API's routes may use, advertised in preflight responses.
+const ALLOWED_METHODS: &str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+
+/// True when `req` is a genuine WebSocket upgrade handshake: a `Connection`
+/// header naming `upgrade` (among possibly other tokens) and an `Upgrade`
+/// header naming `websocket`.
+pub fn is_upgrade_request(req: &ServiceRequest) -> bool {
+    let connection_has_upgrade = req
+        .headers()
+        .get(CONNECTION)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|token| token.trim().eq_ignore_ascii_case("upgrade"))
+        });
+
+    let upgrade_is_websocket = req
+        .headers()
+        .get(UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+
+    connection_has_upgrade && upgrade_is_websocket
+}
+
+/// True when `req` is a CORS preflight request: an `OPTIONS` request naming
+/// the method it's probing for via `Access-Control-Request-Method`.
+pub fn is_preflight_request(req: &ServiceRequest) -> bool {
+    req.method() == Method::OPTIONS && req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+/// Insert `Vary: Origin` whenever `origin` is present, and the CORS
+/// `Access-Control-Allow-{Origin,Credentials}` headers when it is also
+/// allowed by `policy`.
+///
+/// Returns whether the origin was allowed, so callers can gate
+/// preflight-only headers (`Access-Control-Allow-{Methods,Headers}`) on the
+/// same check.
+fn apply_cors_headers(
+    headers: &mut HeaderMap,
+    policy: &OriginPolicy,
+    origin: Option<&HeaderValue>,
+) -> bool {
+    let Some(origin) = origin else {
+        return false;
+    };
+    headers.insert(VARY, HeaderValue::from_static("Origin"));
+
+    let allowed = origin
+        .to_str()
+        .ok()
+        .and_then(|value| Url::parse(value).ok())
+        .is_some_and(|parsed| policy.is_allowed(&parsed));
+    if allowed {
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+        headers.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+    }
+    allowed
+}
+
+fn apply_headers(headers: &mut HeaderMap, policy: &OriginPolicy, origin: Option<&HeaderValue>) {
+    headers.insert(
+        HeaderName::from_static("x-content-type-options"),
+        HeaderValue::from_static("nosniff"),
+    );
+    headers.insert(
+        HeaderName::from_static("x-frame-options"),
+        HeaderValue::from_static("DENY"),
+    );
+    headers.insert(
+        HeaderName::from_static("permissions-policy"),
+        HeaderValue::from_static("geolocation=(), camera=(), microphone=()"),
+    );
+
+    apply_cors_headers(headers, policy, origin);
+}
+
+/// Build the `204 No Content` response to a preflight request.
+///
+/// CORS headers (including `Access-Control-Allow-{Methods,Headers}`) are
+/// only set when the request's `Origin` is on `policy`'s allow-list — the
+/// same check [`apply_headers`] performs for ordinary responses — so a
+/// disallowed origin gets an unhelpful `204` a browser will still block on.
+fn preflight_response(req: ServiceRequest, policy: &OriginPolicy) -> ServiceResponse<BoxBody> {
+    let origin = req.headers().get(ORIGIN).cloned();
+    let requested_headers = req.headers().get(ACCESS_CONTROL_REQUEST_HEADERS).cloned();
+    let (http_req, _payload) = req.into_parts();
+
+    let mut response = HttpResponse::NoContent().finish();
+    let allowed = apply_cors_headers(response.headers_mut(), policy, origin.as_ref());
+    if allowed {
+        response.headers_mut().insert(
+            ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static(ALLOWED_METHODS),
+        );
+        if let Some(requested_headers) = requested_headers {
+            response
+                .headers_mut()
+                .insert(ACCESS_CONTROL_ALLOW_HEADERS, requested_headers);
+        }
+    }
+
+    ServiceResponse::new(http_req, response)
+}
+
+/// Middleware factory wrapping every response with CORS and security headers.
+///
+/// # Examples
+/// ```
+/// use actix_web::App;
+/// use backend::inbound::ws::origin::OriginPolicy;
+/// use backend::SecurityHeaders;
+///
+/// let app = App::new().wrap(SecurityHeaders::new(OriginPolicy::default_allow_list()));
+/// ```
+#[derive(Clone)]
+pub struct SecurityHeaders {
+    policy: Arc<OriginPolicy>,
+}
+
+impl SecurityHeaders {
+    /// Build the middleware from the allow-list it should enforce.
+    #[must_use]
+    pub fn new(policy: OriginPolicy) -> Self {
+        Self {
+            policy: Arc::new(policy),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SecurityHeaders
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SecurityHeadersMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SecurityHeadersMiddleware {
+            service,
+            policy: self.policy.clone(),
+        }))
+    }
+}
+
+/// Service wrapper produced by [`SecurityHeaders`].
+///
+/// Applications should not use this type directly.
+pub struct SecurityHeadersMiddleware<S> {
+    service: S,
+    policy: Arc<OriginPolicy>,
+}
+
+impl<S, B> Service<ServiceRequest> for SecurityHeadersMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if is_upgrade_request(&req) {
+            let fut = self.service.call(req);
+            return Box::pin(async move { Ok(fut.await?.map_into_boxed_body()) });
+        }
+
+        if is_preflight_request(&req) {
+            let policy = self.policy.clone();
+            return Box::pin(async move { Ok(preflight_response(req, &policy)) });
+        }
+
+        let policy = self.policy.clone();
+        let origin = req.headers().get(ORIGIN).cloned();
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let mut res = fut.await?.map_into_boxed_body();
+            apply_headers(res.response_mut().headers_mut(), &policy, origin.as_ref());
+            Ok(res)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+    use rstest::rstest;
+
+    fn header(value: &str) -> HeaderValue {
+        HeaderValue::from_str(value).expect("valid header value")
+    }
+
+    fn upgrade_request() -> ServiceRequest {
+        test::TestRequest::get()
+            .insert_header((CONNECTION, "Upgrade"))
+            .insert_header((UPGRADE, "websocket"))
+            .to_srv_request()
+    }
+
+    #[rstest]
+    fn detects_genuine_upgrade_requests() {
+        assert!(is_upgrade_request(&upgrade_request()));
+    }
+
+    #[rstest]
+    #[case::no_headers(test::TestRequest::get())]
+    #[case::connection_only(test::TestRequest::get().insert_header((CONNECTION, "keep-alive")))]
+    #[case::upgrade_header_without_connection(
+        test::TestRequest::get().insert_header((UPGRADE, "websocket"))
+    )]
+    fn rejects_non_upgrade_requests(#[case] request: test::TestRequest) {
+        assert!(!is_upgrade_request(&request.to_srv_request()));
+    }
+
+    #[actix_web::test]
+    async fn adds_security_headers_to_ordinary_responses() {
+        let app = test::init_service(
+            App::new()
+                .wrap(SecurityHeaders::new(OriginPolicy::default_allow_list()))
+                .route("/", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(
+            res.headers().get("x-frame-options"),
+            Some(&header("DENY"))
+        );
+        assert_eq!(
+            res.headers().get("x-content-type-options"),
+            Some(&header("nosniff"))
+        );
+    }
+
+    #[actix_web::test]
+    async fn echoes_allowed_origin_with_credentials() {
+        let app = test::init_service(
+            App::new()
+                .wrap(SecurityHeaders::new(OriginPolicy::default_allow_list()))
+                .route("/", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((ORIGIN, "https://yourdomain.example"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(&header("https://yourdomain.example"))
+        );
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some(&header("true"))
+        );
+    }
+
+    #[actix_web::test]
+    async fn omits_cors_headers_for_disallowed_origin() {
+        let app = test::init_service(
+            App::new()
+                .wrap(SecurityHeaders::new(OriginPolicy::default_allow_list()))
+                .route("/", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((ORIGIN, "https://evil.example"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    #[actix_web::test]
+    async fn leaves_upgrade_responses_undecorated() {
+        let app = test::init_service(
+            App::new()
+                .wrap(SecurityHeaders::new(OriginPolicy::default_allow_list()))
+                .route(
+                    "/",
+                    web::get().to(|| async { HttpResponse::SwitchingProtocols().finish() }),
+                ),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((CONNECTION, "Upgrade"))
+            .insert_header((UPGRADE, "websocket"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert!(res.headers().get("x-frame-options").is_none());
+    }
+
+    #[actix_web::test]
+    async fn answers_preflight_for_allowed_origin_without_routing() {
+        let app = test::init_service(
+            App::new()
+                .wrap(SecurityHeaders::new(OriginPolicy::default_allow_list()))
+                .route("/", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        )
+        .await;
+
+        let req = test::TestRequest::with_uri("/")
+            .method(Method::OPTIONS)
+            .insert_header((ORIGIN, "https://yourdomain.example"))
+            .insert_header((ACCESS_CONTROL_REQUEST_METHOD, "POST"))
+            .insert_header((ACCESS_CONTROL_REQUEST_HEADERS, "content-type"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), actix_web::http::StatusCode::NO_CONTENT);
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(&header("https://yourdomain.example"))
+        );
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_METHODS),
+            Some(&header(ALLOWED_METHODS))
+        );
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_HEADERS),
+            Some(&header("content-type"))
+        );
+    }
+
+    #[actix_web::test]
+    async fn answers_preflight_without_cors_headers_for_disallowed_origin() {
+        let app = test::init_service(
+            App::new()
+                .wrap(SecurityHeaders::new(OriginPolicy::default_allow_list()))
+                .route("/", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        )
+        .await;
+
+        let req = test::TestRequest::with_uri("/")
+            .method(Method::OPTIONS)
+            .insert_header((ORIGIN, "https://evil.example"))
+            .insert_header((ACCESS_CONTROL_REQUEST_METHOD, "POST"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), actix_web::http::StatusCode::NO_CONTENT);
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_METHODS).is_none());
+    }
+
+    #[rstest]
+    fn regular_options_request_without_request_method_is_not_preflight() {
+        let req = test::TestRequest::with_uri("/")
+            .method(Method::OPTIONS)
+            .to_srv_request();
+        assert!(!is_preflight_request(&req));
+    }
+}
@@ -0,0 +1,343 @@
+//! In-memory, bounded LRU implementation of the `RouteCache` port.
+//!
+//! This adapter gives deployments a cheap first-tier cache in front of
+//! whatever persistent cache they configure (such as
+//! [`super::RedisRouteCache`]). Entries are evicted least-recently-used
+//! first once the configured capacity is reached, and may additionally
+//! carry a per-entry time-to-live so stale plans are treated as misses.
+//!
+//! Entries are content-addressed: each plan is stored alongside the
+//! [`PayloadHash`] of its canonical JSON form, recomputed and checked on
+//! every read. A mismatch — e.g. a truncated write or a collision introduced
+//! by a future external backend — is treated as a cache miss rather than
+//! handed back to the caller, and logged as a [`CacheIntegrityError`].
+
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::domain::ports::{CacheIntegrityError, RouteCache, RouteCacheError, RouteCacheKey};
+use crate::domain::{PayloadHash, canonicalize_and_hash};
+
+struct CacheEntry<P> {
+    plan: P,
+    payload_hash: PayloadHash,
+    inserted_at: Instant,
+}
+
+/// Serialise `plan` to canonical JSON and hash it.
+fn hash_plan<P: Serialize>(plan: &P) -> Result<PayloadHash, RouteCacheError> {
+    let value = serde_json::to_value(plan)
+        .map_err(|err| RouteCacheError::serialization(err.to_string()))?;
+    Ok(canonicalize_and_hash(&value))
+}
+
+struct LruState<P> {
+    entries: HashMap<RouteCacheKey, CacheEntry<P>>,
+    /// Recency order, least-recently-used first.
+    order: VecDeque<RouteCacheKey>,
+}
+
+impl<P> LruState<P> {
+    fn touch(&mut self, key: &RouteCacheKey) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key.clone());
+    }
+
+    fn remove(&mut self, key: &RouteCacheKey) {
+        self.entries.remove(key);
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            self.order.remove(pos);
+        }
+    }
+}
+
+/// Bounded, least-recently-used cache of canonicalised route plans.
+///
+/// Capacity is fixed at construction; inserting beyond capacity evicts the
+/// least-recently-used entry. An optional TTL additionally expires entries
+/// lazily: an expired entry is treated as a miss and removed on the access
+/// that discovers it. Hit/miss counts are tracked for observability and
+/// read with [`LruRouteCache::hits`] / [`LruRouteCache::misses`].
+pub struct LruRouteCache<P> {
+    capacity: usize,
+    ttl: Option<Duration>,
+    state: Mutex<LruState<P>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    _marker: PhantomData<P>,
+}
+
+impl<P> LruRouteCache<P> {
+    /// Create a cache holding at most `capacity` entries with no expiry.
+    ///
+    /// A `capacity` of zero means every entry is evicted immediately after
+    /// insertion, so `get` always misses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ttl: None,
+            state: Mutex::new(LruState {
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Create a cache holding at most `capacity` entries, each expiring
+    /// `ttl` after insertion.
+    pub fn with_ttl(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..Self::new(capacity)
+        }
+    }
+
+    /// Number of cache hits observed so far.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of cache misses observed so far, including TTL expiries.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn is_expired(&self, entry: &CacheEntry<P>) -> bool {
+        self.ttl
+            .is_some_and(|ttl| entry.inserted_at.elapsed() > ttl)
+    }
+}
+
+#[async_trait]
+impl<P: Clone + Send + Sync + Serialize> RouteCache for LruRouteCache<P> {
+    type Plan = P;
+
+    async fn get(&self, key: &RouteCacheKey) -> Result<Option<Self::Plan>, RouteCacheError> {
+        let mut state = self.state.lock().expect("cache mutex poisoned");
+
+        let Some(entry) = state.entries.get(key) else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        };
+
+        if self.is_expired(entry) {
+            state.remove(key);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+
+        let plan = entry.plan.clone();
+        let expected_hash = entry.payload_hash.clone();
+        let actual_hash = hash_plan(&plan)?;
+
+        if actual_hash != expected_hash {
+            warn!(
+                error = %CacheIntegrityError {
+                    key: key.clone(),
+                    expected: expected_hash.to_hex(),
+                    actual: actual_hash.to_hex(),
+                },
+                "cached route plan failed integrity verification; treating as a miss"
+            );
+            state.remove(key);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+
+        state.touch(key);
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Ok(Some(plan))
+    }
+
+    async fn put(&self, key: &RouteCacheKey, plan: &Self::Plan) -> Result<(), RouteCacheError> {
+        let payload_hash = hash_plan(plan)?;
+        let mut state = self.state.lock().expect("cache mutex poisoned");
+
+        state.entries.insert(
+            key.clone(),
+            CacheEntry {
+                plan: plan.clone(),
+                payload_hash,
+                inserted_at: Instant::now(),
+            },
+        );
+        state.touch(key);
+
+        // Evict after inserting, not before: evicting first leaves a
+        // capacity-0 cache holding one entry (nothing was over capacity
+        // yet), breaking the "capacity 0 always misses" guarantee.
+        while state.entries.len() > self.capacity {
+            let Some(lru_key) = state.order.pop_front() else {
+                break;
+            };
+            state.entries.remove(&lru_key);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+    struct TestPlan(u32);
+
+    fn key(value: &str) -> RouteCacheKey {
+        RouteCacheKey::new(value).expect("valid key")
+    }
+
+    #[tokio::test]
+    async fn cache_misses_when_empty() {
+        let cache: LruRouteCache<TestPlan> = LruRouteCache::new(2);
+
+        let result = cache.get(&key("a")).await.expect("get succeeds");
+
+        assert!(result.is_none());
+        assert_eq!(cache.misses(), 1);
+        assert_eq!(cache.hits(), 0);
+    }
+
+    #[tokio::test]
+    async fn cache_hits_after_put() {
+        let cache: LruRouteCache<TestPlan> = LruRouteCache::new(2);
+        cache
+            .put(&key("a"), &TestPlan(1))
+            .await
+            .expect("put succeeds");
+
+        let result = cache.get(&key("a")).await.expect("get succeeds");
+
+        assert_eq!(result, Some(TestPlan(1)));
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 0);
+    }
+
+    #[tokio::test]
+    async fn cache_evicts_least_recently_used_on_overflow() {
+        let cache: LruRouteCache<TestPlan> = LruRouteCache::new(2);
+        cache
+            .put(&key("a"), &TestPlan(1))
+            .await
+            .expect("put succeeds");
+        cache
+            .put(&key("b"), &TestPlan(2))
+            .await
+            .expect("put succeeds");
+        // Touch "a" so "b" becomes the least-recently-used entry.
+        cache.get(&key("a")).await.expect("get succeeds");
+        cache
+            .put(&key("c"), &TestPlan(3))
+            .await
+            .expect("put succeeds");
+
+        assert!(cache.get(&key("b")).await.expect("get succeeds").is_none());
+        assert_eq!(
+            cache.get(&key("a")).await.expect("get succeeds"),
+            Some(TestPlan(1))
+        );
+        assert_eq!(
+            cache.get(&key("c")).await.expect("get succeeds"),
+            Some(TestPlan(3))
+        );
+    }
+
+    #[tokio::test]
+    async fn cache_treats_expired_entry_as_miss() {
+        let cache: LruRouteCache<TestPlan> =
+            LruRouteCache::with_ttl(2, Duration::from_millis(1));
+        cache
+            .put(&key("a"), &TestPlan(1))
+            .await
+            .expect("put succeeds");
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let result = cache.get(&key("a")).await.expect("get succeeds");
+
+        assert!(result.is_none());
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[tokio::test]
+    async fn cache_treats_hash_mismatch_as_miss() {
+        let cache: LruRouteCache<TestPlan> = LruRouteCache::new(2);
+        cache
+            .put(&key("a"), &TestPlan(1))
+            .await
+            .expect("put succeeds");
+        {
+            let mut state = cache.state.lock().expect("cache mutex poisoned");
+            let entry = state.entries.get_mut(&key("a")).expect("entry present");
+            entry.payload_hash = hash_plan(&TestPlan(2)).expect("hash succeeds");
+        }
+
+        let result = cache.get(&key("a")).await.expect("get succeeds");
+
+        assert!(result.is_none());
+        assert_eq!(cache.misses(), 1);
+        assert!(
+            cache
+                .state
+                .lock()
+                .expect("cache mutex poisoned")
+                .entries
+                .get(&key("a"))
+                .is_none(),
+            "corrupted entry should be evicted"
+        );
+    }
+
+    #[tokio::test]
+    async fn cache_with_zero_capacity_always_misses() {
+        let cache: LruRouteCache<TestPlan> = LruRouteCache::new(0);
+        cache
+            .put(&key("a"), &TestPlan(1))
+            .await
+            .expect("put succeeds");
+
+        let result = cache.get(&key("a")).await.expect("get succeeds");
+
+        assert!(result.is_none());
+        assert!(
+            cache
+                .state
+                .lock()
+                .expect("cache mutex poisoned")
+                .entries
+                .is_empty(),
+            "a zero-capacity cache must not retain the inserted entry"
+        );
+    }
+
+    #[tokio::test]
+    async fn cache_overwrite_refreshes_recency() {
+        let cache: LruRouteCache<TestPlan> = LruRouteCache::new(1);
+        cache
+            .put(&key("a"), &TestPlan(1))
+            .await
+            .expect("put succeeds");
+        cache
+            .put(&key("a"), &TestPlan(2))
+            .await
+            .expect("put succeeds");
+
+        let result = cache.get(&key("a")).await.expect("get succeeds");
+
+        assert_eq!(result, Some(TestPlan(2)));
+    }
+}
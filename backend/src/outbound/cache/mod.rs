@@ -1,20 +1,9 @@
-//! Placeholder for future Redis cache adapter.
+//! Cache adapters for the `RouteCache` port.
 //!
-//! This module provides a stub implementation of the `RouteCache` port that
-//! always returns cache misses. It serves as a structural placeholder until
-//! the Redis-backed implementation is completed.
-//!
-//! # Future Implementation
-//!
-//! The full Redis implementation will:
-//! - Use `bb8-redis` for connection pooling
-//! - Serialize plans with `serde_json` or MessagePack
-//! - Apply TTL with jitter to prevent thundering herd on expiry
-//! - Use namespaced keys (`route:v1:<sha256>`) for version-safe invalidation
-//!
-//! # Roadmap
-//!
-//! See `docs/backend-roadmap.md` for the Redis cache implementation tasks.
+//! [`LruRouteCache`] is a bounded in-process first-tier cache.
+//! [`RedisRouteCache`] is the shared, persistent second-tier cache backed by
+//! Redis. [`StubRouteCache`] remains available as a no-op placeholder for
+//! callers that don't want either (e.g. tests that don't exercise caching).
 
 use std::marker::PhantomData;
 
@@ -22,6 +11,12 @@ use async_trait::async_trait;
 
 use crate::domain::ports::{RouteCache, RouteCacheError, RouteCacheKey};
 
+mod lru_route_cache;
+mod redis_route_cache;
+
+pub use lru_route_cache::LruRouteCache;
+pub use redis_route_cache::RedisRouteCache;
+
 /// Stub cache implementation that always returns cache misses.
 ///
 /// This placeholder implements the `RouteCache` port with no-op behaviour,
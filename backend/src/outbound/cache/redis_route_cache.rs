@@ -0,0 +1,266 @@
+//! Redis-backed implementation of the `RouteCache` port.
+//!
+//! Plans are serialised with MessagePack (via `rmp-serde`) rather than JSON
+//! for compactness, and stored under a namespaced, version-prefixed key
+//! (`route:v1:<sha256-of-RouteCacheKey>`) so a future schema change can
+//! invalidate every cached plan cleanly by rolling the version segment.
+//! Expiry carries jitter proportional to the base TTL so concurrently
+//! cached plans do not all expire on the same tick and stampede the route
+//! planner (thundering herd).
+//!
+//! Entries are content-addressed the same way as [`super::LruRouteCache`]:
+//! each plan is stored alongside the [`PayloadHash`] of its canonical JSON
+//! form, recomputed and checked on every read. A mismatch (a stored plan
+//! that no longer hashes to the hash written with it) is treated as a cache
+//! miss rather than handed back to the caller, and logged as a
+//! [`CacheIntegrityError`]. The hash covers the plan only, not the key, so a
+//! well-formed entry stored under the wrong key is not detected. Bytes that
+//! fail to decode are also treated as a miss, logged as a deserialisation
+//! failure.
+
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bb8::Pool;
+use bb8_redis::RedisConnectionManager;
+use bb8_redis::redis::AsyncCommands;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::domain::ports::{CacheIntegrityError, RouteCache, RouteCacheError, RouteCacheKey};
+use crate::domain::{PayloadHash, canonicalize_and_hash};
+
+/// Key namespace segment. Bump this when the cached plan schema changes so
+/// stale entries are abandoned rather than misread.
+const KEY_VERSION: &str = "v1";
+
+/// Derive the namespaced, version-prefixed Redis key for `key`.
+fn redis_key(key: &RouteCacheKey) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(key.as_str().as_bytes());
+    format!("route:{KEY_VERSION}:{:x}", hasher.finalize())
+}
+
+/// On-wire envelope pairing a cached plan with the hash it was written
+/// with, so a read can detect corruption before handing the plan back.
+#[derive(Deserialize)]
+struct CachedEntry<P> {
+    plan: P,
+    payload_hash: [u8; 32],
+}
+
+/// Borrowing counterpart of [`CachedEntry`] used to serialise a plan without
+/// requiring `P: Clone`.
+#[derive(Serialize)]
+struct CachedEntryRef<'a, P> {
+    plan: &'a P,
+    payload_hash: [u8; 32],
+}
+
+/// Serialise `plan` to canonical JSON and hash it.
+fn hash_plan<P: Serialize>(plan: &P) -> Result<PayloadHash, RouteCacheError> {
+    let value = serde_json::to_value(plan)
+        .map_err(|err| RouteCacheError::serialization(err.to_string()))?;
+    Ok(canonicalize_and_hash(&value))
+}
+
+/// Redis-backed [`RouteCache`] pooled via `bb8-redis`.
+///
+/// `P` is the cached plan type; it must round-trip through MessagePack.
+pub struct RedisRouteCache<P> {
+    pool: Pool<RedisConnectionManager>,
+    base_ttl: Duration,
+    jitter_fraction: f64,
+    _marker: PhantomData<P>,
+}
+
+impl<P> RedisRouteCache<P> {
+    /// Create a cache writing entries with `base_ttl` plus up to
+    /// `jitter_fraction * base_ttl` of additional random expiry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `jitter_fraction` is negative.
+    pub fn new(pool: Pool<RedisConnectionManager>, base_ttl: Duration, jitter_fraction: f64) -> Self {
+        assert!(
+            jitter_fraction >= 0.0,
+            "jitter_fraction must not be negative"
+        );
+        Self {
+            pool,
+            base_ttl,
+            jitter_fraction,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Compute this write's jittered expiry, in whole seconds.
+    fn jittered_ttl_seconds(&self) -> u64 {
+        Self::jitter_ttl_seconds(self.base_ttl, self.jitter_fraction)
+    }
+
+    /// Core jitter computation behind [`Self::jittered_ttl_seconds`],
+    /// extracted as an associated function so tests can exercise it
+    /// directly without constructing a pooled Redis connection.
+    fn jitter_ttl_seconds(base_ttl: Duration, jitter_fraction: f64) -> u64 {
+        let base = base_ttl.as_secs_f64();
+        let jitter_ceiling = base * jitter_fraction;
+        let jitter = if jitter_ceiling > 0.0 {
+            rand::thread_rng().gen_range(0.0..=jitter_ceiling)
+        } else {
+            0.0
+        };
+        (base + jitter).round() as u64
+    }
+}
+
+#[async_trait]
+impl<P: Serialize + DeserializeOwned + Send + Sync> RouteCache for RedisRouteCache<P> {
+    type Plan = P;
+
+    async fn get(&self, key: &RouteCacheKey) -> Result<Option<Self::Plan>, RouteCacheError> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|err| RouteCacheError::backend(err.to_string()))?;
+
+        let bytes: Option<Vec<u8>> = conn
+            .get(redis_key(key))
+            .await
+            .map_err(|err| RouteCacheError::backend(err.to_string()))?;
+
+        let Some(bytes) = bytes else {
+            return Ok(None);
+        };
+
+        let entry: CachedEntry<Self::Plan> = match rmp_serde::from_slice(&bytes) {
+            Ok(entry) => entry,
+            Err(error) => {
+                warn!(
+                    key = %key,
+                    error = %error,
+                    "failed to deserialise cached route plan; treating as a miss"
+                );
+                return Ok(None);
+            }
+        };
+
+        let expected_hash = PayloadHash::from_bytes(&entry.payload_hash);
+        let actual_hash = hash_plan(&entry.plan)?;
+
+        if actual_hash != expected_hash {
+            warn!(
+                error = %CacheIntegrityError {
+                    key: key.clone(),
+                    expected: expected_hash.to_hex(),
+                    actual: actual_hash.to_hex(),
+                },
+                "cached route plan failed integrity verification; treating as a miss"
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(entry.plan))
+    }
+
+    async fn put(&self, key: &RouteCacheKey, plan: &Self::Plan) -> Result<(), RouteCacheError> {
+        let payload_hash = hash_plan(plan)?;
+        let entry = CachedEntryRef {
+            plan,
+            payload_hash: *payload_hash.as_bytes(),
+        };
+        let bytes = rmp_serde::to_vec(&entry)
+            .map_err(|err| RouteCacheError::serialization(err.to_string()))?;
+
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|err| RouteCacheError::backend(err.to_string()))?;
+
+        conn.set_ex::<_, _, ()>(redis_key(key), bytes, self.jittered_ttl_seconds())
+            .await
+            .map_err(|err| RouteCacheError::backend(err.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redis_key_is_namespaced_and_version_prefixed() {
+        let key = RouteCacheKey::new("route:user:1").expect("valid key");
+
+        let derived = redis_key(&key);
+
+        assert!(derived.starts_with("route:v1:"));
+        assert_eq!(derived.len(), "route:v1:".len() + 64);
+    }
+
+    #[test]
+    fn redis_key_is_deterministic() {
+        let key = RouteCacheKey::new("route:user:1").expect("valid key");
+
+        assert_eq!(redis_key(&key), redis_key(&key));
+    }
+
+    #[test]
+    fn jittered_ttl_never_exceeds_configured_ceiling() {
+        let base_ttl = Duration::from_secs(60);
+        let jitter_fraction = 0.1;
+        let base = base_ttl.as_secs_f64();
+        let ceiling = (base + base * jitter_fraction).round() as u64;
+
+        for _ in 0..100 {
+            let total = RedisRouteCache::<()>::jitter_ttl_seconds(base_ttl, jitter_fraction);
+
+            assert!(total >= base_ttl.as_secs());
+            assert!(total <= ceiling);
+        }
+    }
+
+    #[test]
+    fn zero_jitter_fraction_never_panics() {
+        let base_ttl = Duration::from_secs(30);
+
+        let total = RedisRouteCache::<()>::jitter_ttl_seconds(base_ttl, 0.0);
+
+        assert_eq!(total, base_ttl.as_secs());
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct TestPlan(u32);
+
+    #[test]
+    fn cached_entry_round_trips_through_messagepack() {
+        let plan = TestPlan(42);
+        let payload_hash = hash_plan(&plan).expect("hash succeeds");
+        let entry = CachedEntryRef {
+            plan: &plan,
+            payload_hash: *payload_hash.as_bytes(),
+        };
+
+        let bytes = rmp_serde::to_vec(&entry).expect("entry serialises");
+        let decoded: CachedEntry<TestPlan> =
+            rmp_serde::from_slice(&bytes).expect("entry deserialises");
+
+        assert_eq!(decoded.plan, plan);
+        assert_eq!(PayloadHash::from_bytes(&decoded.payload_hash), payload_hash);
+    }
+
+    #[test]
+    fn hash_plan_detects_tampering() {
+        let original = hash_plan(&TestPlan(1)).expect("hash succeeds");
+        let tampered = hash_plan(&TestPlan(2)).expect("hash succeeds");
+
+        assert_ne!(original, tampered);
+    }
+}
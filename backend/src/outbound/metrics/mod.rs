@@ -4,5 +4,7 @@
 //! ports. All adapters here are feature-gated behind the `metrics` feature.
 
 mod prometheus_idempotency;
+mod prometheus_route_annotations_telemetry;
 
 pub use prometheus_idempotency::PrometheusIdempotencyMetrics;
+pub use prometheus_route_annotations_telemetry::PrometheusRouteAnnotationsTelemetry;
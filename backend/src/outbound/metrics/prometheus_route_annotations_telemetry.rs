@@ -0,0 +1,180 @@
+//! Prometheus adapter for route annotation idempotency/concurrency metrics.
+//!
+//! This adapter exports [`RouteAnnotationsTelemetry`] events to Prometheus via
+//! the `prometheus` crate. Metrics are registered with a provided registry and
+//! exposed via the `/metrics` endpoint.
+
+use async_trait::async_trait;
+use prometheus::{CounterVec, Opts, Registry};
+
+use crate::domain::MutationType;
+use crate::domain::ports::{RouteAnnotationsTelemetry, RouteAnnotationsTelemetryError};
+
+/// Prometheus-backed route annotations telemetry recorder.
+///
+/// Records fresh executions, replays, conflicts, duplicate-key races, and
+/// revision mismatches as increments to a single counter metric with labels
+/// for event and mutation type.
+///
+/// # Metric Specification
+///
+/// - **Name**: `wildside_route_annotations_idempotency_events_total`
+/// - **Type**: Counter
+/// - **Labels**:
+///   - `event`: `fresh`, `replay`, `conflict`, `duplicate_race`, or
+///     `revision_mismatch`
+///   - `mutation_type`: the [`MutationType::as_str`] of the triggering request
+pub struct PrometheusRouteAnnotationsTelemetry {
+    events_total: CounterVec,
+}
+
+impl PrometheusRouteAnnotationsTelemetry {
+    /// Create and register metrics with the given registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metric cannot be registered (e.g., if a metric
+    /// with the same name already exists in the registry).
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let events_total = CounterVec::new(
+            Opts::new(
+                "wildside_route_annotations_idempotency_events_total",
+                "Total route annotation idempotency/concurrency lifecycle events",
+            ),
+            &["event", "mutation_type"],
+        )?;
+        registry.register(Box::new(events_total.clone()))?;
+        Ok(Self { events_total })
+    }
+
+    fn record(&self, event: &str, mutation_type: MutationType) {
+        self.events_total
+            .with_label_values(&[event, mutation_type.as_str()])
+            .inc();
+    }
+}
+
+#[async_trait]
+impl RouteAnnotationsTelemetry for PrometheusRouteAnnotationsTelemetry {
+    async fn record_fresh(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        self.record("fresh", mutation_type);
+        Ok(())
+    }
+
+    async fn record_replay(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        self.record("replay", mutation_type);
+        Ok(())
+    }
+
+    async fn record_conflict(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        self.record("conflict", mutation_type);
+        Ok(())
+    }
+
+    async fn record_duplicate_race(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        self.record("duplicate_race", mutation_type);
+        Ok(())
+    }
+
+    async fn record_revision_mismatch(
+        &self,
+        mutation_type: MutationType,
+    ) -> Result<(), RouteAnnotationsTelemetryError> {
+        self.record("revision_mismatch", mutation_type);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_metric_with_registry() {
+        let registry = Registry::new();
+        let metrics = PrometheusRouteAnnotationsTelemetry::new(&registry)
+            .expect("metric registration should succeed");
+
+        metrics.record("fresh", MutationType::Notes);
+
+        let families = registry.gather();
+        assert!(
+            families
+                .iter()
+                .any(|f| f.name() == "wildside_route_annotations_idempotency_events_total"),
+            "metric should be registered"
+        );
+    }
+
+    #[tokio::test]
+    async fn record_fresh_increments_counter() {
+        let registry = Registry::new();
+        let metrics = PrometheusRouteAnnotationsTelemetry::new(&registry)
+            .expect("metric registration should succeed");
+
+        metrics
+            .record_fresh(MutationType::Notes)
+            .await
+            .expect("recording should succeed");
+
+        let counter = metrics
+            .events_total
+            .with_label_values(&["fresh", "notes"]);
+        assert_eq!(counter.get() as u64, 1);
+    }
+
+    #[tokio::test]
+    async fn record_revision_mismatch_increments_counter() {
+        let registry = Registry::new();
+        let metrics = PrometheusRouteAnnotationsTelemetry::new(&registry)
+            .expect("metric registration should succeed");
+
+        metrics
+            .record_revision_mismatch(MutationType::Progress)
+            .await
+            .expect("recording should succeed");
+        metrics
+            .record_revision_mismatch(MutationType::Progress)
+            .await
+            .expect("recording should succeed");
+
+        let counter = metrics
+            .events_total
+            .with_label_values(&["revision_mismatch", "progress"]);
+        assert_eq!(counter.get() as u64, 2);
+    }
+
+    #[tokio::test]
+    async fn records_distinct_counters_per_mutation_type() {
+        let registry = Registry::new();
+        let metrics = PrometheusRouteAnnotationsTelemetry::new(&registry)
+            .expect("metric registration should succeed");
+
+        metrics
+            .record_duplicate_race(MutationType::AnnotationsBatch)
+            .await
+            .expect("recording should succeed");
+
+        let counter = metrics
+            .events_total
+            .with_label_values(&["duplicate_race", "annotations_batch"]);
+        assert_eq!(counter.get() as u64, 1);
+
+        let other = metrics
+            .events_total
+            .with_label_values(&["duplicate_race", "notes"]);
+        assert_eq!(other.get() as u64, 0);
+    }
+}
@@ -0,0 +1,174 @@
+//! PostgreSQL-backed example data run tracking adapter.
+//!
+//! Backed by a connection pool rather than a single client, so startup
+//! seeding and concurrent request handlers check out a connection per call
+//! instead of serializing on one. See [`super::pool::DbPool::metrics`] for
+//! sizing the pool from observed in-use/idle counts and checkout wait.
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use crate::domain::ports::{ExampleDataRunsError, ExampleDataRunsRepository, SeedingResult};
+
+use super::diesel_helpers::{map_diesel_error_message, map_pool_error_message};
+use super::models::{ExampleDataRunRow, ExampleDataRunSeedMetadataRow, NewExampleDataRunRow};
+use super::pool::{DbPool, PoolError};
+use super::schema::example_data_runs;
+
+const STATUS_PENDING: &str = "pending";
+const STATUS_COMPLETED: &str = "completed";
+
+/// Diesel-backed implementation of the [`ExampleDataRunsRepository`] port.
+#[derive(Clone)]
+pub struct DieselExampleDataRunsRepository {
+    pool: DbPool,
+}
+
+impl DieselExampleDataRunsRepository {
+    /// Create a new repository with the given connection pool.
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_pool_error(error: PoolError) -> ExampleDataRunsError {
+    ExampleDataRunsError::connection(map_pool_error_message(error))
+}
+
+fn map_diesel_error(error: diesel::result::Error, operation: &str) -> ExampleDataRunsError {
+    ExampleDataRunsError::query(map_diesel_error_message(error, operation))
+}
+
+#[async_trait::async_trait]
+impl ExampleDataRunsRepository for DieselExampleDataRunsRepository {
+    async fn try_record_seed(
+        &self,
+        seed_key: &str,
+        user_count: i32,
+        seed: i64,
+    ) -> Result<SeedingResult, ExampleDataRunsError> {
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+        let new_run = NewExampleDataRunRow {
+            seed_key,
+            user_count,
+            seed,
+            status: STATUS_PENDING,
+        };
+
+        let rows_affected = diesel::insert_into(example_data_runs::table)
+            .values(&new_run)
+            .on_conflict(example_data_runs::seed_key)
+            .do_nothing()
+            .execute(&mut conn)
+            .await
+            .map_err(|error| map_diesel_error(error, "example data run claim"))?;
+
+        if rows_affected > 0 {
+            return Ok(SeedingResult::Applied);
+        }
+
+        let recorded: ExampleDataRunSeedMetadataRow = example_data_runs::table
+            .filter(example_data_runs::seed_key.eq(seed_key))
+            .select(ExampleDataRunSeedMetadataRow::as_select())
+            .first(&mut conn)
+            .await
+            .map_err(|error| map_diesel_error(error, "example data run conflict lookup"))?;
+
+        Ok(SeedingResult::AlreadySeeded {
+            recorded_user_count: recorded.user_count,
+            recorded_seed: recorded.seed,
+        })
+    }
+
+    async fn finalize_seed(&self, seed_key: &str) -> Result<(), ExampleDataRunsError> {
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+        diesel::update(
+            example_data_runs::table.filter(
+                example_data_runs::seed_key
+                    .eq(seed_key)
+                    .and(example_data_runs::status.eq(STATUS_PENDING)),
+            ),
+        )
+        .set(example_data_runs::status.eq(STATUS_COMPLETED))
+        .execute(&mut conn)
+        .await
+        .map_err(|error| map_diesel_error(error, "example data run finalize"))?;
+        Ok(())
+    }
+
+    async fn is_seeded(&self, seed_key: &str) -> Result<bool, ExampleDataRunsError> {
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+        let row: Option<ExampleDataRunRow> = example_data_runs::table
+            .filter(example_data_runs::seed_key.eq(seed_key))
+            .select(ExampleDataRunRow::as_select())
+            .first(&mut conn)
+            .await
+            .optional()
+            .map_err(|error| map_diesel_error(error, "example data run lookup"))?;
+
+        Ok(row.is_some_and(|row| row.status == STATUS_COMPLETED))
+    }
+
+    async fn reclaim_abandoned_seed(
+        &self,
+        seed_key: &str,
+        stale_after: std::time::Duration,
+    ) -> Result<bool, ExampleDataRunsError> {
+        let threshold = Utc::now()
+            - chrono::Duration::from_std(stale_after)
+                .map_err(|error| ExampleDataRunsError::query(error.to_string()))?;
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+
+        // Delete rather than refresh `claimed_at`: `try_record_seed` claims
+        // via `ON CONFLICT (seed_key) DO NOTHING`, so a row left in place
+        // would keep blocking every future claim for this seed key.
+        let rows_affected = diesel::delete(
+            example_data_runs::table.filter(
+                example_data_runs::seed_key
+                    .eq(seed_key)
+                    .and(example_data_runs::status.eq(STATUS_PENDING))
+                    .and(example_data_runs::claimed_at.lt(threshold)),
+            ),
+        )
+        .execute(&mut conn)
+        .await
+        .map_err(|error| map_diesel_error(error, "example data run reclaim"))?;
+
+        Ok(rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Regression coverage for error mapping.
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn pool_error_maps_to_connection_error() {
+        let pool_err = PoolError::checkout("connection refused");
+        let persistence_err = map_pool_error(pool_err);
+
+        assert!(matches!(
+            persistence_err,
+            ExampleDataRunsError::Connection { .. }
+        ));
+        assert!(persistence_err.to_string().contains("connection refused"));
+    }
+
+    #[rstest]
+    fn diesel_error_maps_to_query_error() {
+        let diesel_err = diesel::result::Error::NotFound;
+        let persistence_err = map_diesel_error(diesel_err, "example data run lookup");
+
+        assert!(matches!(persistence_err, ExampleDataRunsError::Query { .. }));
+        assert!(
+            persistence_err
+                .to_string()
+                .to_lowercase()
+                .contains("not found")
+        );
+    }
+}
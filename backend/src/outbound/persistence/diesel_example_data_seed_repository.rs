@@ -1,8 +1,10 @@
 //! PostgreSQL-backed example data seeding adapter.
 //!
-//! This adapter implements the `ExampleDataSeedRepository` port, applying
-//! example data within a single transaction. It records the seed run and
-//! inserts or updates users and their preferences atomically.
+//! This adapter implements the `ExampleDataSeedRepository` port, upserting
+//! generated example users and their preferences within a single
+//! transaction. It does not record the seed run itself; that lifecycle is
+//! owned by `ExampleDataRunsRepository`, which callers use to claim the run
+//! before seeding and finalize it afterwards.
 
 use async_trait::async_trait;
 use diesel::prelude::*;
@@ -13,12 +15,12 @@ use tracing::debug;
 
 use crate::domain::ports::{
     ExampleDataSeedRepository, ExampleDataSeedRepositoryError, ExampleDataSeedRequest,
-    ExampleDataSeedUser, SeedingResult,
+    ExampleDataSeedUser,
 };
 
-use super::models::{NewExampleDataRunRow, NewUserPreferencesRow, NewUserRow};
+use super::models::{NewUserPreferencesRow, NewUserRow};
 use super::pool::{DbPool, PoolError};
-use super::schema::{example_data_runs, user_preferences, users};
+use super::schema::{user_preferences, users};
 
 /// Diesel-backed implementation of the example data seeding repository.
 #[derive(Clone)]
@@ -125,72 +127,47 @@ impl ExampleDataSeedRepository for DieselExampleDataSeedRepository {
     async fn seed_example_data(
         &self,
         request: ExampleDataSeedRequest,
-    ) -> Result<SeedingResult, ExampleDataSeedRepositoryError> {
-        let ExampleDataSeedRequest {
-            seed_key,
-            user_count,
-            seed,
-            users,
-        } = request;
+    ) -> Result<(), ExampleDataSeedRepositoryError> {
+        let ExampleDataSeedRequest { users, .. } = request;
         let (user_rows, preference_rows) = map_seed_users(&users)?;
         let mut conn = self.pool.get().await.map_err(map_pool_error)?;
 
-        let result = conn
-            .transaction(|conn| {
-                async move {
-                    let new_run = NewExampleDataRunRow {
-                        seed_key: seed_key.as_str(),
-                        user_count,
-                        seed,
-                    };
-
-                    let rows_affected = diesel::insert_into(example_data_runs::table)
-                        .values(&new_run)
-                        .on_conflict(example_data_runs::seed_key)
-                        .do_nothing()
-                        .execute(conn)
-                        .await?;
-
-                    if rows_affected == 0 {
-                        return Ok(SeedingResult::AlreadySeeded);
-                    }
-
-                    if user_rows.is_empty() {
-                        return Ok(SeedingResult::Applied);
-                    }
-
-                    diesel::insert_into(users::table)
-                        .values(&user_rows)
-                        .on_conflict(users::id)
-                        .do_update()
-                        .set(users::display_name.eq(excluded(users::display_name)))
-                        .execute(conn)
-                        .await?;
-
-                    diesel::insert_into(user_preferences::table)
-                        .values(&preference_rows)
-                        .on_conflict(user_preferences::user_id)
-                        .do_update()
-                        .set((
-                            user_preferences::interest_theme_ids
-                                .eq(excluded(user_preferences::interest_theme_ids)),
-                            user_preferences::safety_toggle_ids
-                                .eq(excluded(user_preferences::safety_toggle_ids)),
-                            user_preferences::unit_system
-                                .eq(excluded(user_preferences::unit_system)),
-                            user_preferences::revision.eq(excluded(user_preferences::revision)),
-                        ))
-                        .execute(conn)
-                        .await?;
-
-                    Ok(SeedingResult::Applied)
-                }
-                .scope_boxed()
-            })
-            .await
-            .map_err(map_diesel_error)?;
-
-        Ok(result)
+        if user_rows.is_empty() {
+            return Ok(());
+        }
+
+        conn.transaction(|conn| {
+            async move {
+                diesel::insert_into(users::table)
+                    .values(&user_rows)
+                    .on_conflict(users::id)
+                    .do_update()
+                    .set(users::display_name.eq(excluded(users::display_name)))
+                    .execute(conn)
+                    .await?;
+
+                diesel::insert_into(user_preferences::table)
+                    .values(&preference_rows)
+                    .on_conflict(user_preferences::user_id)
+                    .do_update()
+                    .set((
+                        user_preferences::interest_theme_ids
+                            .eq(excluded(user_preferences::interest_theme_ids)),
+                        user_preferences::safety_toggle_ids
+                            .eq(excluded(user_preferences::safety_toggle_ids)),
+                        user_preferences::unit_system
+                            .eq(excluded(user_preferences::unit_system)),
+                        user_preferences::revision.eq(excluded(user_preferences::revision)),
+                    ))
+                    .execute(conn)
+                    .await?;
+
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+        .map_err(map_diesel_error)
     }
 }
 
@@ -0,0 +1,149 @@
+//! PostgreSQL-backed durable job queue adapter.
+//!
+//! Claiming is implemented as a single `UPDATE ... WHERE id = (SELECT ...
+//! FOR UPDATE SKIP LOCKED) RETURNING ...` statement so concurrent workers
+//! never race to claim the same row: Postgres's row-level locking does the
+//! coordination, not application code. Diesel's query DSL has no way to
+//! express that subquery-with-locking shape, so the claim is hand-written
+//! SQL; the other operations use ordinary Diesel queries against the
+//! `job_queue` table.
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{BigInt, Jsonb, Text};
+use diesel_async::RunQueryDsl;
+
+use crate::domain::ports::{ClaimedJob, JobQueueError, JobQueueRepository};
+
+use super::diesel_helpers::{map_diesel_error_message, map_pool_error_message};
+use super::models::NewJobRow;
+use super::pool::{DbPool, PoolError};
+use super::schema::job_queue;
+
+/// Diesel-backed implementation of the [`JobQueueRepository`] port.
+#[derive(Clone)]
+pub struct DieselJobQueueRepository {
+    pool: DbPool,
+}
+
+impl DieselJobQueueRepository {
+    /// Create a new job queue repository with the given connection pool.
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+const CLAIM_SQL: &str = r#"
+UPDATE job_queue
+SET status = 'running', worker_id = $2, heartbeat = now()
+WHERE id = (
+    SELECT id FROM job_queue
+    WHERE queue = $1 AND status = 'new'
+    ORDER BY id
+    LIMIT 1
+    FOR UPDATE SKIP LOCKED
+)
+RETURNING id, queue, payload
+"#;
+
+#[derive(QueryableByName)]
+struct ClaimedJobRow {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Text)]
+    queue: String,
+    #[diesel(sql_type = Jsonb)]
+    payload: serde_json::Value,
+}
+
+impl From<ClaimedJobRow> for ClaimedJob {
+    fn from(row: ClaimedJobRow) -> Self {
+        Self {
+            id: row.id,
+            queue: row.queue,
+            payload: row.payload,
+        }
+    }
+}
+
+fn map_pool_error(error: PoolError) -> JobQueueError {
+    JobQueueError::connection(map_pool_error_message(error))
+}
+
+fn map_diesel_error(error: diesel::result::Error, operation: &str) -> JobQueueError {
+    JobQueueError::query(map_diesel_error_message(error, operation))
+}
+
+#[async_trait::async_trait]
+impl JobQueueRepository for DieselJobQueueRepository {
+    async fn enqueue(&self, queue: &str, payload: serde_json::Value) -> Result<(), JobQueueError> {
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+        let new_job = NewJobRow {
+            queue,
+            payload,
+            status: "new",
+        };
+        diesel::insert_into(job_queue::table)
+            .values(&new_job)
+            .execute(&mut conn)
+            .await
+            .map_err(|error| map_diesel_error(error, "job queue enqueue"))?;
+        Ok(())
+    }
+
+    async fn claim(
+        &self,
+        queue: &str,
+        worker_id: &str,
+    ) -> Result<Option<ClaimedJob>, JobQueueError> {
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+        let row: Option<ClaimedJobRow> = sql_query(CLAIM_SQL)
+            .bind::<Text, _>(queue)
+            .bind::<Text, _>(worker_id)
+            .get_result(&mut conn)
+            .await
+            .optional()
+            .map_err(|error| map_diesel_error(error, "job queue claim"))?;
+        Ok(row.map(ClaimedJob::from))
+    }
+
+    async fn heartbeat(&self, job_id: i64) -> Result<(), JobQueueError> {
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+        diesel::update(job_queue::table.filter(job_queue::id.eq(job_id)))
+            .set(job_queue::heartbeat.eq(diesel::dsl::now))
+            .execute(&mut conn)
+            .await
+            .map_err(|error| map_diesel_error(error, "job queue heartbeat"))?;
+        Ok(())
+    }
+
+    async fn complete(&self, job_id: i64) -> Result<(), JobQueueError> {
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+        diesel::delete(job_queue::table.filter(job_queue::id.eq(job_id)))
+            .execute(&mut conn)
+            .await
+            .map_err(|error| map_diesel_error(error, "job queue complete"))?;
+        Ok(())
+    }
+
+    async fn release_stale(&self, older_than: DateTime<Utc>) -> Result<u64, JobQueueError> {
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+        let rows_affected = diesel::update(
+            job_queue::table.filter(
+                job_queue::status
+                    .eq("running")
+                    .and(job_queue::heartbeat.lt(older_than)),
+            ),
+        )
+        .set((
+            job_queue::status.eq("new"),
+            job_queue::worker_id.eq(Option::<String>::None),
+            job_queue::heartbeat.eq(Option::<DateTime<Utc>>::None),
+        ))
+        .execute(&mut conn)
+        .await
+        .map_err(|error| map_diesel_error(error, "job queue release_stale"))?;
+        Ok(rows_affected as u64)
+    }
+}
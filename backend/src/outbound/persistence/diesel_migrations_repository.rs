@@ -0,0 +1,177 @@
+//! PostgreSQL-backed schema migration runner.
+//!
+//! Applies pending migrations inside a single transaction, guarded by a
+//! `schema_migrations` ledger keyed by a plain, monotonically increasing
+//! `idx` rather than a linked "parent pointer" chain, so gaps and reordering
+//! stay easy to reason about and query. Migrations already recorded in the
+//! ledger have their checksum re-verified against the supplied SQL so
+//! tampered-with or edited history is caught at startup rather than
+//! silently skipped.
+
+use std::collections::HashMap;
+
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use sha2::{Digest, Sha256};
+
+use crate::domain::ports::{Migration, MigrationsRepository, MigrationsRepositoryError};
+
+use super::diesel_helpers::map_pool_error_message;
+use super::models::NewSchemaMigrationRow;
+use super::pool::{DbPool, PoolError};
+use super::schema::schema_migrations;
+
+/// Diesel-backed implementation of the [`MigrationsRepository`] port.
+#[derive(Clone)]
+pub struct DieselMigrationsRepository {
+    pool: DbPool,
+}
+
+impl DieselMigrationsRepository {
+    /// Create a new migrations repository with the given connection pool.
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn checksum(sql: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(sql.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn map_pool_error(error: PoolError) -> MigrationsRepositoryError {
+    MigrationsRepositoryError::connection(map_pool_error_message(error))
+}
+
+/// Error type threaded through the transaction closure, so a checksum
+/// mismatch can short-circuit the transaction alongside ordinary Diesel
+/// errors.
+enum TransactionError {
+    Diesel(diesel::result::Error),
+    ChecksumMismatch { idx: i64, name: String },
+}
+
+impl From<diesel::result::Error> for TransactionError {
+    fn from(error: diesel::result::Error) -> Self {
+        Self::Diesel(error)
+    }
+}
+
+impl From<TransactionError> for MigrationsRepositoryError {
+    fn from(error: TransactionError) -> Self {
+        match error {
+            TransactionError::Diesel(error) => Self::query(error.to_string()),
+            TransactionError::ChecksumMismatch { idx, name } => {
+                Self::checksum_mismatch(idx, name)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationsRepository for DieselMigrationsRepository {
+    async fn apply_pending(
+        &self,
+        migrations: &[Migration],
+    ) -> Result<Vec<Migration>, MigrationsRepositoryError> {
+        let mut sorted: Vec<&Migration> = migrations.iter().collect();
+        sorted.sort_by_key(|migration| migration.idx);
+
+        let mut conn = self.pool.get().await.map_err(map_pool_error)?;
+
+        let applied = conn
+            .transaction(|conn| {
+                async move {
+                    let recorded_rows: Vec<(i64, String)> = schema_migrations::table
+                        .select((schema_migrations::idx, schema_migrations::checksum))
+                        .load(conn)
+                        .await?;
+                    let recorded: HashMap<i64, String> = recorded_rows.into_iter().collect();
+
+                    for migration in &sorted {
+                        if let Some(recorded_checksum) = recorded.get(&migration.idx) {
+                            if *recorded_checksum != checksum(&migration.sql) {
+                                return Err(TransactionError::ChecksumMismatch {
+                                    idx: migration.idx,
+                                    name: migration.name.clone(),
+                                });
+                            }
+                        }
+                    }
+
+                    let mut newly_applied = Vec::new();
+                    for migration in sorted
+                        .iter()
+                        .filter(|migration| !recorded.contains_key(&migration.idx))
+                    {
+                        diesel::sql_query(migration.sql.as_str())
+                            .execute(conn)
+                            .await?;
+
+                        diesel::insert_into(schema_migrations::table)
+                            .values(NewSchemaMigrationRow {
+                                idx: migration.idx,
+                                name: &migration.name,
+                                checksum: checksum(&migration.sql),
+                            })
+                            .execute(conn)
+                            .await?;
+
+                        newly_applied.push((*migration).clone());
+                    }
+
+                    Ok(newly_applied)
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(MigrationsRepositoryError::from)?;
+
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Regression coverage for checksum hashing and error mapping.
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn checksum_is_deterministic() {
+        assert_eq!(checksum("CREATE TABLE foo ()"), checksum("CREATE TABLE foo ()"));
+    }
+
+    #[rstest]
+    fn checksum_differs_for_different_sql() {
+        assert_ne!(checksum("CREATE TABLE foo ()"), checksum("CREATE TABLE bar ()"));
+    }
+
+    #[rstest]
+    fn pool_error_maps_to_connection_error() {
+        let pool_err = PoolError::checkout("connection refused");
+        let persistence_err = map_pool_error(pool_err);
+
+        assert!(matches!(
+            persistence_err,
+            MigrationsRepositoryError::Connection { .. }
+        ));
+    }
+
+    #[rstest]
+    fn checksum_mismatch_transaction_error_maps_to_dedicated_variant() {
+        let error = TransactionError::ChecksumMismatch {
+            idx: 3,
+            name: "add_users_index".to_owned(),
+        };
+        let persistence_err = MigrationsRepositoryError::from(error);
+
+        assert!(matches!(
+            persistence_err,
+            MigrationsRepositoryError::ChecksumMismatch { idx: 3, .. }
+        ));
+    }
+}
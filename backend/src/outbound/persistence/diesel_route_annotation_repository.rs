@@ -6,11 +6,15 @@
 
 use async_trait::async_trait;
 use diesel::prelude::*;
-use diesel_async::RunQueryDsl;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
 use tracing::debug;
 use uuid::Uuid;
 
-use crate::domain::ports::{RouteAnnotationRepository, RouteAnnotationRepositoryError};
+use crate::domain::ports::{
+    AnnotationBatchWrite, AnnotationBatchWriteOutcome, RouteAnnotationRepository,
+    RouteAnnotationRepositoryError,
+};
 use crate::domain::{RouteNote, RouteProgress, UserId};
 
 use super::models::{
@@ -279,64 +283,12 @@ impl RouteAnnotationRepository for DieselRouteAnnotationRepository {
         expected_revision: Option<u32>,
     ) -> Result<(), RouteAnnotationRepositoryError> {
         let mut conn = self.pool.get().await.map_err(map_pool_error)?;
-        let revision_i32 = cast_revision_for_db(note.revision);
-
-        match expected_revision {
-            None => {
-                let new_row = NewRouteNoteRow {
-                    id: note.id,
-                    route_id: note.route_id,
-                    poi_id: note.poi_id,
-                    user_id: *note.user_id.as_uuid(),
-                    body: &note.body,
-                    revision: revision_i32,
-                };
-                diesel::insert_into(route_notes::table)
-                    .values(&new_row)
-                    .execute(&mut conn)
-                    .await
-                    .map(|_| ())
-                    .map_err(map_diesel_error)
-            }
-            Some(expected) => {
-                let expected_i32 = cast_revision_for_db(expected);
-                let update = RouteNoteUpdate {
-                    poi_id: note.poi_id,
-                    body: &note.body,
-                    revision: revision_i32,
-                };
-
-                let updated_rows = diesel::update(route_notes::table)
-                    .filter(
-                        route_notes::id
-                            .eq(note.id)
-                            .and(route_notes::revision.eq(expected_i32)),
-                    )
-                    .set(&update)
-                    .execute(&mut conn)
-                    .await
-                    .map_err(map_diesel_error)?;
-
-                let result = execute_optimistic_update(updated_rows).await;
-                if let Err(ref e) = result
-                    && is_zero_rows_error(e)
-                {
-                    return Err(handle_note_update_failure(&mut conn, note.id, expected).await);
-                }
-                result
-            }
-        }
+        save_note_with_conn(&mut conn, note, expected_revision).await
     }
 
     async fn delete_note(&self, note_id: &Uuid) -> Result<bool, RouteAnnotationRepositoryError> {
         let mut conn = self.pool.get().await.map_err(map_pool_error)?;
-
-        let deleted = diesel::delete(route_notes::table.filter(route_notes::id.eq(note_id)))
-            .execute(&mut conn)
-            .await
-            .map_err(map_diesel_error)?;
-
-        Ok(deleted > 0)
+        delete_note_with_conn(&mut conn, note_id).await
     }
 
     // --- Progress ---
@@ -369,56 +321,214 @@ impl RouteAnnotationRepository for DieselRouteAnnotationRepository {
         expected_revision: Option<u32>,
     ) -> Result<(), RouteAnnotationRepositoryError> {
         let mut conn = self.pool.get().await.map_err(map_pool_error)?;
-        let revision_i32 = cast_revision_for_db(progress.revision);
-
-        match expected_revision {
-            None => {
-                let new_row = NewRouteProgressRow {
-                    route_id: progress.route_id,
-                    user_id: *progress.user_id.as_uuid(),
-                    visited_stop_ids: &progress.visited_stop_ids,
-                    revision: revision_i32,
-                };
-                diesel::insert_into(route_progress::table)
-                    .values(&new_row)
-                    .execute(&mut conn)
-                    .await
-                    .map(|_| ())
-                    .map_err(map_diesel_error)
-            }
-            Some(expected) => {
-                let expected_i32 = cast_revision_for_db(expected);
-                let update = RouteProgressUpdate {
-                    visited_stop_ids: &progress.visited_stop_ids,
-                    revision: revision_i32,
-                };
-
-                let updated_rows = diesel::update(route_progress::table)
-                    .filter(
-                        route_progress::route_id
-                            .eq(progress.route_id)
-                            .and(route_progress::user_id.eq(progress.user_id.as_uuid()))
-                            .and(route_progress::revision.eq(expected_i32)),
-                    )
-                    .set(&update)
-                    .execute(&mut conn)
-                    .await
-                    .map_err(map_diesel_error)?;
-
-                let result = execute_optimistic_update(updated_rows).await;
-                if let Err(ref e) = result
-                    && is_zero_rows_error(e)
-                {
-                    return Err(handle_progress_update_failure(
-                        &mut conn,
-                        progress.route_id,
-                        *progress.user_id.as_uuid(),
-                        expected,
-                    )
-                    .await);
+        save_progress_with_conn(&mut conn, progress, expected_revision).await
+    }
+
+    async fn apply_batch(
+        &self,
+        writes: &[AnnotationBatchWrite],
+    ) -> Result<Vec<AnnotationBatchWriteOutcome>, (usize, RouteAnnotationRepositoryError)> {
+        let mut conn = self.pool.get().await.map_err(|error| (0, map_pool_error(error)))?;
+        let failure: std::cell::Cell<Option<(usize, RouteAnnotationRepositoryError)>> =
+            std::cell::Cell::new(None);
+
+        let transaction_result = conn
+            .transaction(|conn| {
+                async move {
+                    let mut outcomes = Vec::with_capacity(writes.len());
+                    for (index, write) in writes.iter().enumerate() {
+                        match apply_batch_write_with_conn(conn, write).await {
+                            Ok(outcome) => outcomes.push(outcome),
+                            Err(error) => {
+                                failure.set(Some((index, error)));
+                                return Err(diesel::result::Error::RollbackTransaction);
+                            }
+                        }
+                    }
+                    Ok(outcomes)
                 }
-                result
+                .scope_boxed()
+            })
+            .await;
+
+        transaction_result.map_err(|_| {
+            failure.take().unwrap_or_else(|| {
+                (
+                    0,
+                    RouteAnnotationRepositoryError::query("batch transaction rolled back"),
+                )
+            })
+        })
+    }
+}
+
+/// Insert or update a note on `conn`, sharing logic between the single-write
+/// `save_note` call and the per-item writes inside [`apply_batch`].
+async fn save_note_with_conn<C>(
+    conn: &mut C,
+    note: &RouteNote,
+    expected_revision: Option<u32>,
+) -> Result<(), RouteAnnotationRepositoryError>
+where
+    C: diesel_async::AsyncConnection<Backend = diesel::pg::Pg> + Send,
+{
+    let revision_i32 = cast_revision_for_db(note.revision);
+
+    match expected_revision {
+        None => {
+            let new_row = NewRouteNoteRow {
+                id: note.id,
+                route_id: note.route_id,
+                poi_id: note.poi_id,
+                user_id: *note.user_id.as_uuid(),
+                body: &note.body,
+                revision: revision_i32,
+            };
+            diesel::insert_into(route_notes::table)
+                .values(&new_row)
+                .execute(conn)
+                .await
+                .map(|_| ())
+                .map_err(map_diesel_error)
+        }
+        Some(expected) => {
+            let expected_i32 = cast_revision_for_db(expected);
+            let update = RouteNoteUpdate {
+                poi_id: note.poi_id,
+                body: &note.body,
+                revision: revision_i32,
+            };
+
+            let updated_rows = diesel::update(route_notes::table)
+                .filter(
+                    route_notes::id
+                        .eq(note.id)
+                        .and(route_notes::revision.eq(expected_i32)),
+                )
+                .set(&update)
+                .execute(conn)
+                .await
+                .map_err(map_diesel_error)?;
+
+            let result = execute_optimistic_update(updated_rows).await;
+            if let Err(ref e) = result
+                && is_zero_rows_error(e)
+            {
+                return Err(handle_note_update_failure(conn, note.id, expected).await);
             }
+            result
+        }
+    }
+}
+
+/// Delete a note by ID on `conn`, sharing logic between the single-write
+/// `delete_note` call and the per-item writes inside [`apply_batch`].
+async fn delete_note_with_conn<C>(
+    conn: &mut C,
+    note_id: &Uuid,
+) -> Result<bool, RouteAnnotationRepositoryError>
+where
+    C: diesel_async::AsyncConnection<Backend = diesel::pg::Pg> + Send,
+{
+    let deleted = diesel::delete(route_notes::table.filter(route_notes::id.eq(note_id)))
+        .execute(conn)
+        .await
+        .map_err(map_diesel_error)?;
+
+    Ok(deleted > 0)
+}
+
+/// Insert or update progress on `conn`, sharing logic between the
+/// single-write `save_progress` call and the per-item writes inside
+/// [`apply_batch`].
+async fn save_progress_with_conn<C>(
+    conn: &mut C,
+    progress: &RouteProgress,
+    expected_revision: Option<u32>,
+) -> Result<(), RouteAnnotationRepositoryError>
+where
+    C: diesel_async::AsyncConnection<Backend = diesel::pg::Pg> + Send,
+{
+    let revision_i32 = cast_revision_for_db(progress.revision);
+
+    match expected_revision {
+        None => {
+            let new_row = NewRouteProgressRow {
+                route_id: progress.route_id,
+                user_id: *progress.user_id.as_uuid(),
+                visited_stop_ids: &progress.visited_stop_ids,
+                revision: revision_i32,
+            };
+            diesel::insert_into(route_progress::table)
+                .values(&new_row)
+                .execute(conn)
+                .await
+                .map(|_| ())
+                .map_err(map_diesel_error)
+        }
+        Some(expected) => {
+            let expected_i32 = cast_revision_for_db(expected);
+            let update = RouteProgressUpdate {
+                visited_stop_ids: &progress.visited_stop_ids,
+                revision: revision_i32,
+            };
+
+            let updated_rows = diesel::update(route_progress::table)
+                .filter(
+                    route_progress::route_id
+                        .eq(progress.route_id)
+                        .and(route_progress::user_id.eq(progress.user_id.as_uuid()))
+                        .and(route_progress::revision.eq(expected_i32)),
+                )
+                .set(&update)
+                .execute(conn)
+                .await
+                .map_err(map_diesel_error)?;
+
+            let result = execute_optimistic_update(updated_rows).await;
+            if let Err(ref e) = result
+                && is_zero_rows_error(e)
+            {
+                return Err(handle_progress_update_failure(
+                    conn,
+                    progress.route_id,
+                    *progress.user_id.as_uuid(),
+                    expected,
+                )
+                .await);
+            }
+            result
+        }
+    }
+}
+
+/// Execute a single planned batch write on `conn`, inside the caller's
+/// transaction.
+async fn apply_batch_write_with_conn<C>(
+    conn: &mut C,
+    write: &AnnotationBatchWrite,
+) -> Result<AnnotationBatchWriteOutcome, RouteAnnotationRepositoryError>
+where
+    C: diesel_async::AsyncConnection<Backend = diesel::pg::Pg> + Send,
+{
+    match write {
+        AnnotationBatchWrite::UpsertNote {
+            note,
+            expected_revision,
+        } => {
+            save_note_with_conn(conn, note, *expected_revision).await?;
+            Ok(AnnotationBatchWriteOutcome::NoteUpserted)
+        }
+        AnnotationBatchWrite::DeleteNote { note_id } => {
+            let deleted = delete_note_with_conn(conn, note_id).await?;
+            Ok(AnnotationBatchWriteOutcome::NoteDeleted(deleted))
+        }
+        AnnotationBatchWrite::UpdateProgress {
+            progress,
+            expected_revision,
+        } => {
+            save_progress_with_conn(conn, progress, *expected_revision).await?;
+            Ok(AnnotationBatchWriteOutcome::ProgressUpdated)
         }
     }
 }
@@ -8,7 +8,9 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
-use crate::domain::ports::{WalkSessionRepository, WalkSessionRepositoryError};
+use crate::domain::ports::{
+    SummaryCursor, SummaryPage, SummaryPageQuery, WalkSessionRepository, WalkSessionRepositoryError,
+};
 use crate::domain::{
     UserId, WalkCompletionSummary, WalkPrimaryStat, WalkPrimaryStatDraft, WalkSecondaryStat,
     WalkSecondaryStatDraft, WalkSession, WalkSessionDraft,
@@ -181,31 +183,77 @@ impl WalkSessionRepository for DieselWalkSessionRepository {
         row.map(row_to_walk_session).transpose()
     }
 
-    async fn list_completion_summaries_for_user(
+    async fn list_completion_summaries_page(
         &self,
         user_id: &UserId,
-    ) -> Result<Vec<WalkCompletionSummary>, WalkSessionRepositoryError> {
+        query: SummaryPageQuery,
+    ) -> Result<SummaryPage, WalkSessionRepositoryError> {
+        // A zero limit can't produce a `next_cursor`: the cursor is derived
+        // from the last returned summary, and there is no last summary to
+        // derive it from. Short-circuit rather than querying for a page that
+        // would otherwise report `has_more` with no way to resume from it.
+        if query.limit == 0 {
+            return Ok(SummaryPage::default());
+        }
+
         let mut conn = self.pool.get().await.map_err(map_pool_error)?;
 
-        let rows: Vec<WalkSessionRow> = walk_sessions::table
+        let mut db_query = walk_sessions::table
             .filter(
                 walk_sessions::user_id
                     .eq(user_id.as_uuid())
                     .and(walk_sessions::ended_at.is_not_null()),
             )
+            .into_boxed();
+
+        if let Some(since) = query.since {
+            db_query = db_query.filter(walk_sessions::ended_at.ge(since));
+        }
+        if let Some(until) = query.until {
+            db_query = db_query.filter(walk_sessions::ended_at.le(until));
+        }
+        if let Some(after) = &query.after {
+            db_query = db_query.filter(
+                walk_sessions::ended_at.lt(after.ended_at()).or(walk_sessions::ended_at
+                    .eq(after.ended_at())
+                    .and(walk_sessions::id.lt(after.session_id()))),
+            );
+        }
+
+        // Fetch one extra row to determine whether a further page remains
+        // without a second round trip.
+        let fetch_limit = query.limit.saturating_add(1).min(i64::MAX as usize) as i64;
+
+        let mut rows: Vec<WalkSessionRow> = db_query
             .order((walk_sessions::ended_at.desc(), walk_sessions::id.desc()))
+            .limit(fetch_limit)
             .select(WalkSessionRow::as_select())
             .load(&mut conn)
             .await
             .map_err(map_diesel_error)?;
 
-        rows.into_iter()
+        let has_more = rows.len() > query.limit;
+        if has_more {
+            rows.truncate(query.limit);
+        }
+
+        let summaries: Vec<WalkCompletionSummary> = rows
+            .into_iter()
             .map(|row| {
                 row_to_walk_session(row)?
                     .completion_summary()
                     .map_err(|err| WalkSessionRepositoryError::query(err.to_string()))
             })
-            .collect()
+            .collect::<Result<_, _>>()?;
+
+        let next_cursor = has_more
+            .then(|| summaries.last().map(SummaryCursor::from))
+            .flatten();
+
+        Ok(SummaryPage {
+            summaries,
+            next_cursor,
+        })
     }
 }
 
@@ -37,7 +37,10 @@ mod diesel_example_data_runs_repository;
 mod diesel_example_data_seed_repository;
 pub(crate) mod diesel_helpers;
 mod diesel_idempotency_repository;
+mod diesel_idempotency_store;
+mod diesel_job_queue_repository;
 mod diesel_login_service;
+mod diesel_migrations_repository;
 mod diesel_offline_bundle_repository;
 mod diesel_osm_ingestion_provenance_repository;
 mod diesel_osm_poi_repository;
@@ -61,7 +64,10 @@ pub use diesel_descriptor_repository::DieselDescriptorRepository;
 pub use diesel_example_data_runs_repository::DieselExampleDataRunsRepository;
 pub use diesel_example_data_seed_repository::DieselExampleDataSeedRepository;
 pub use diesel_idempotency_repository::DieselIdempotencyRepository;
+pub use diesel_idempotency_store::DieselIdempotencyStore;
+pub use diesel_job_queue_repository::DieselJobQueueRepository;
 pub use diesel_login_service::DieselLoginService;
+pub use diesel_migrations_repository::DieselMigrationsRepository;
 pub use diesel_offline_bundle_repository::DieselOfflineBundleRepository;
 pub use diesel_osm_ingestion_provenance_repository::DieselOsmIngestionProvenanceRepository;
 pub use diesel_osm_poi_repository::DieselOsmPoiRepository;
@@ -14,7 +14,7 @@ use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use uuid::Uuid;
 
-use super::schema::users;
+use super::schema::{example_data_runs, job_queue, schema_migrations, users};
 
 /// Row struct for reading from the users table.
 ///
@@ -52,3 +52,60 @@ pub(crate) struct NewUserRow<'a> {
 pub(crate) struct UserUpdate<'a> {
     pub display_name: &'a str,
 }
+
+/// Row struct for reading an example data run's lifecycle state.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = example_data_runs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub(crate) struct ExampleDataRunRow {
+    pub status: String,
+    pub claimed_at: DateTime<Utc>,
+}
+
+/// Row struct for reading an already-seeded run's recorded parameters.
+///
+/// Used to surface drift detection in
+/// [`crate::domain::ports::SeedingResult::AlreadySeeded`] when a seed key
+/// conflicts with an existing row.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = example_data_runs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub(crate) struct ExampleDataRunSeedMetadataRow {
+    pub user_count: i32,
+    pub seed: i64,
+}
+
+/// Insertable struct for claiming a new example data seed run.
+///
+/// `status` always starts as `"pending"`; callers transition it to
+/// `"completed"` via `finalize_seed` once seeding succeeds. `claimed_at`
+/// defaults to `NOW()` via the database schema.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = example_data_runs)]
+pub(crate) struct NewExampleDataRunRow<'a> {
+    pub seed_key: &'a str,
+    pub user_count: i32,
+    pub seed: i64,
+    pub status: &'static str,
+}
+
+/// Insertable struct for recording a newly applied migration.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = schema_migrations)]
+pub(crate) struct NewSchemaMigrationRow<'a> {
+    pub idx: i64,
+    pub name: &'a str,
+    pub checksum: String,
+}
+
+/// Insertable struct for enqueueing a new job.
+///
+/// `status` always starts as `"new"`; `worker_id` and `heartbeat` are left
+/// unset until a worker claims the row.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = job_queue)]
+pub(crate) struct NewJobRow<'a> {
+    pub queue: &'a str,
+    pub payload: serde_json::Value,
+    pub status: &'static str,
+}
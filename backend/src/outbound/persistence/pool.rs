@@ -8,10 +8,16 @@
 //!
 //! - Uses `diesel-async`'s native async support rather than `spawn_blocking`
 //! - Pool checkout is non-blocking and respects timeout configuration
-//! - Connections are validated before use to detect stale connections
+//! - Connections are pinged before checkout (`test_on_check_out`) so a
+//!   connection dropped by the server is detected and replaced rather than
+//!   handed to a caller as if it were live
 //! - All errors are mapped to domain-level `PoolError` variants
+//! - [`DbPool::metrics`] exposes in-use/idle counts and the most recent
+//!   checkout wait, so operators can size the pool from observed behaviour
 
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use diesel_async::pooled_connection::bb8::{Pool, PooledConnection};
 use diesel_async::pooled_connection::AsyncDieselConnectionManager;
@@ -134,6 +140,23 @@ impl PoolConfig {
 #[derive(Clone)]
 pub struct DbPool {
     inner: Pool<AsyncPgConnection>,
+    last_wait_nanos: Arc<AtomicU64>,
+}
+
+/// Point-in-time connection pool utilization and recent checkout latency.
+///
+/// Exposed so operators can size [`PoolConfig::with_max_size`] and
+/// [`PoolConfig::with_min_idle`] from observed behaviour rather than
+/// guesswork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolMetrics {
+    /// Connections currently checked out.
+    pub in_use: u32,
+    /// Connections sitting idle, ready to be checked out.
+    pub idle: u32,
+    /// Wall-clock time the most recent `get()` call spent waiting for a
+    /// connection to become available.
+    pub last_wait: Duration,
 }
 
 impl DbPool {
@@ -163,24 +186,50 @@ impl DbPool {
             .max_size(config.max_size)
             .min_idle(config.min_idle)
             .connection_timeout(config.connection_timeout)
+            .test_on_check_out(true)
             .build(manager)
             .await
             .map_err(|err| PoolError::build(err.to_string()))?;
 
-        Ok(Self { inner: pool })
+        Ok(Self {
+            inner: pool,
+            last_wait_nanos: Arc::new(AtomicU64::new(0)),
+        })
     }
 
     /// Get a connection from the pool.
     ///
+    /// Fails fast with `PoolError::Checkout` rather than blocking
+    /// indefinitely when the pool is exhausted or every candidate
+    /// connection fails its pre-checkout liveness ping within the
+    /// configured timeout.
+    ///
     /// # Errors
     ///
     /// Returns `PoolError::Checkout` if a connection cannot be obtained within
     /// the configured timeout.
     pub async fn get(&self) -> Result<PooledConnection<'_, AsyncPgConnection>, PoolError> {
-        self.inner
+        let started = Instant::now();
+        let result = self
+            .inner
             .get()
             .await
-            .map_err(|err| PoolError::checkout(err.to_string()))
+            .map_err(|err| PoolError::checkout(err.to_string()));
+        self.last_wait_nanos.store(
+            u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+        result
+    }
+
+    /// Report current pool utilization and the most recent checkout latency.
+    pub fn metrics(&self) -> PoolMetrics {
+        let state = self.inner.state();
+        PoolMetrics {
+            in_use: state.connections - state.idle_connections,
+            idle: state.idle_connections,
+            last_wait: Duration::from_nanos(self.last_wait_nanos.load(Ordering::Relaxed)),
+        }
     }
 }
 
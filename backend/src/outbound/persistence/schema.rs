@@ -56,3 +56,94 @@ diesel::table! {
         updated_at -> Timestamptz,
     }
 }
+
+// -----------------------------------------------------------------------------
+// example_data_runs table
+// -----------------------------------------------------------------------------
+//
+// Tracks which example-data seeds have been claimed and/or applied, guarding
+// against duplicate seeding on concurrent startups or restarts. Recording is
+// two-phase: `try_record_seed` inserts a `pending` row, and `finalize_seed`
+// flips it to `completed` once the generated users/preferences have landed,
+// so a crash mid-seed leaves the row `pending` (retryable) rather than
+// falsely `completed`.
+//
+// Columns:
+//
+// - id: Primary key (SERIAL)
+// - seed_key: Seed name, unique (part of the `ON CONFLICT DO NOTHING` guard)
+// - user_count: Number of users generated by this seed
+// - seed: RNG seed value used for deterministic generation
+// - status: Lifecycle state, "pending" or "completed" (a plain text column;
+//   no code path writes any other value)
+// - claimed_at: When the row was last (re)claimed; used to detect abandoned
+//   `pending` rows whose worker crashed before finalizing
+
+diesel::table! {
+    example_data_runs (id) {
+        id -> Int4,
+        seed_key -> Text,
+        user_count -> Int4,
+        seed -> BigInt,
+        status -> Text,
+        claimed_at -> Timestamptz,
+    }
+}
+
+// -----------------------------------------------------------------------------
+// schema_migrations table
+// -----------------------------------------------------------------------------
+//
+// Ledger of applied schema migrations, ordered by a plain monotonically
+// increasing integer rather than a linked "parent pointer" chain, so gaps
+// and reordering are easy to reason about and query.
+//
+// Columns:
+//
+// - idx: Migration position (primary key; not a SERIAL, since the caller
+//   assigns it explicitly as part of the migration's identity)
+// - name: Stable, human-readable migration name
+// - checksum: SHA-256 hex digest of the migration's SQL, used to detect
+//   tampered-with or edited history
+// - applied_at: When the migration was applied
+
+diesel::table! {
+    schema_migrations (idx) {
+        idx -> BigInt,
+        name -> Text,
+        checksum -> Text,
+        applied_at -> Timestamptz,
+    }
+}
+
+// -----------------------------------------------------------------------------
+// job_queue table
+// -----------------------------------------------------------------------------
+//
+// Durable background job queue. Workers claim the oldest `new` row for a
+// given queue name with `SELECT ... FOR UPDATE SKIP LOCKED` so concurrent
+// workers never collide, then flip it to `running` and stamp `heartbeat`.
+// `release_stale` resets rows whose heartbeat has gone quiet back to `new`
+// so a crashed worker's job is retried by someone else.
+//
+// Columns:
+//
+// - id: Primary key (BIGSERIAL), also the claim order
+// - queue: Logical queue name (indexed; jobs are claimed per-queue)
+// - payload: JSONB job payload
+// - status: Job lifecycle state ("new" or "running")
+// - worker_id: Identifier of the worker currently holding the job, if any
+// - heartbeat: Last time the holding worker renewed its claim
+// - created_at: Record creation timestamp
+
+diesel::table! {
+    job_queue (id) {
+        id -> BigInt,
+        queue -> Text,
+        payload -> Jsonb,
+        status -> Text,
+        worker_id -> Nullable<Text>,
+        heartbeat -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
@@ -6,12 +6,18 @@ use std::net::SocketAddr;
 #[cfg(feature = "metrics")]
 use actix_web_prom::PrometheusMetrics;
 
+use backend::inbound::ws::origin::OriginPolicy;
+use backend::outbound::persistence::DbPool;
+
 /// Builder-style configuration for creating the HTTP server.
 pub struct ServerConfig {
     pub(crate) key: Key,
     pub(crate) cookie_secure: bool,
     pub(crate) same_site: SameSite,
     pub(crate) bind_addr: SocketAddr,
+    pub(crate) ws_compression: bool,
+    pub(crate) db_pool: Option<DbPool>,
+    pub(crate) ws_origin_policy: Option<OriginPolicy>,
     #[cfg(feature = "metrics")]
     pub(crate) prometheus: Option<PrometheusMetrics>,
 }
@@ -25,6 +31,9 @@ impl ServerConfig {
             cookie_secure,
             same_site,
             bind_addr,
+            ws_compression: false,
+            db_pool: None,
+            ws_origin_policy: None,
             #[cfg(feature = "metrics")]
             prometheus: None,
         }
@@ -37,6 +46,53 @@ impl ServerConfig {
         self.bind_addr
     }
 
+    /// Toggle whether the `/ws` upgrade negotiates the private deflate
+    /// compression extension (not RFC 7692 `permessage-deflate`).
+    ///
+    /// Disabled by default; operators can opt in once they've confirmed their
+    /// deployment's intermediaries handle the extension correctly.
+    #[must_use]
+    pub fn with_ws_compression(mut self, enabled: bool) -> Self {
+        self.ws_compression = enabled;
+        self
+    }
+
+    /// Whether compression-extension negotiation is enabled for new `/ws` connections.
+    #[cfg_attr(not(any(test, doctest)), allow(dead_code))]
+    #[must_use]
+    pub fn ws_compression(&self) -> bool {
+        self.ws_compression
+    }
+
+    /// Attach a database connection pool, switching port construction from
+    /// fixtures to the real Diesel-backed adapters.
+    ///
+    /// Left unset, every service falls back to its fixture implementation.
+    #[must_use]
+    pub fn with_db_pool(mut self, db_pool: DbPool) -> Self {
+        self.db_pool = Some(db_pool);
+        self
+    }
+
+    /// Return the configured database pool, if any.
+    #[cfg_attr(not(any(test, doctest)), allow(dead_code))]
+    #[must_use]
+    pub fn db_pool(&self) -> Option<&DbPool> {
+        self.db_pool.as_ref()
+    }
+
+    /// Replace the Origin allow-list used to validate `/ws` upgrades.
+    ///
+    /// Left unset, [`create_server`](super::create_server) keeps
+    /// [`OriginPolicy::default_allow_list`], the placeholder list compiled
+    /// into the binary; deployments with a real domain or preview
+    /// environments must set this explicitly.
+    #[must_use]
+    pub fn with_ws_origin_policy(mut self, origin_policy: OriginPolicy) -> Self {
+        self.ws_origin_policy = Some(origin_policy);
+        self
+    }
+
     #[cfg(feature = "metrics")]
     /// Attach Prometheus middleware to the configuration.
     #[must_use]
@@ -3,6 +3,7 @@
 mod config;
 #[cfg(feature = "metrics")]
 mod metrics;
+mod state_builders;
 
 pub use config::ServerConfig;
 
@@ -20,35 +21,63 @@ use actix_web::{web, App, HttpServer};
 
 #[cfg(debug_assertions)]
 use backend::doc::ApiDoc;
-use backend::domain::ports::{
-    FixtureLoginService, FixtureRouteSubmissionService, FixtureUserInterestsCommand,
-    FixtureUserProfileQuery, FixtureUsersQuery,
-};
+use backend::domain::annotations::retry_worker::{reap_stale_jobs, RouteAnnotationsRetryWorker};
+use backend::domain::idempotency::MutationType;
+use backend::domain::ports::{FixtureRouteSubmissionService, IdempotencyStore, JobQueueRepository};
 use backend::domain::UserOnboardingService;
 use backend::inbound::http::health::{live, ready, HealthState};
+use backend::inbound::http::idempotency_middleware::Idempotent;
 use backend::inbound::http::routes::submit_route;
-use backend::inbound::http::state::{HttpState, HttpStatePorts};
+use backend::inbound::http::state::HttpState;
 use backend::inbound::http::users::{current_user, list_users, login, update_interests};
+use backend::inbound::http::walk_sessions::create_walk_session;
 use backend::inbound::ws;
+use backend::inbound::ws::origin::OriginPolicy;
 use backend::inbound::ws::state::WsState;
-use backend::Trace;
+use backend::{SecurityHeaders, Trace};
+use tracing::warn;
 #[cfg(debug_assertions)]
 use utoipa::OpenApi;
 #[cfg(debug_assertions)]
 use utoipa_swagger_ui::SwaggerUi;
 
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone)]
 struct AppDependencies {
     health_state: web::Data<HealthState>,
     http_state: web::Data<HttpState>,
     ws_state: web::Data<WsState>,
+    idempotency_store: Arc<dyn IdempotencyStore>,
     key: Key,
     cookie_secure: bool,
     same_site: SameSite,
 }
 
+/// Build a fresh session middleware instance with the server's cookie settings.
+///
+/// The `/ws` upgrade endpoint needs the same session cookie the HTTP API
+/// reads, so it is wrapped with a second instance rather than sharing the
+/// `/api/v1` scope's (consumed-on-`.build()`) middleware.
+fn session_middleware(
+    key: Key,
+    cookie_secure: bool,
+    same_site: SameSite,
+) -> SessionMiddleware<CookieSessionStore> {
+    SessionMiddleware::builder(CookieSessionStore::default(), key)
+        .cookie_name("session".into())
+        .cookie_path("/".into())
+        .cookie_secure(cookie_secure)
+        .cookie_http_only(true)
+        .cookie_content_security(CookieContentSecurity::Private)
+        .cookie_same_site(same_site)
+        .session_lifecycle(
+            PersistentSession::default().session_ttl(actix_web::cookie::time::Duration::hours(2)),
+        )
+        .build()
+}
+
 fn build_app(
     deps: AppDependencies,
 ) -> App<
@@ -64,38 +93,46 @@ fn build_app(
         health_state,
         http_state,
         ws_state,
+        idempotency_store,
         key,
         cookie_secure,
         same_site,
     } = deps;
 
-    let session = SessionMiddleware::builder(CookieSessionStore::default(), key)
-        .cookie_name("session".into())
-        .cookie_path("/".into())
-        .cookie_secure(cookie_secure)
-        .cookie_http_only(true)
-        .cookie_content_security(CookieContentSecurity::Private)
-        .cookie_same_site(same_site)
-        .session_lifecycle(
-            PersistentSession::default().session_ttl(actix_web::cookie::time::Duration::hours(2)),
-        )
-        .build();
-
     let api = web::scope("/api/v1")
-        .wrap(session)
+        .wrap(session_middleware(key.clone(), cookie_secure, same_site))
         .service(login)
         .service(list_users)
         .service(current_user)
         .service(update_interests)
         .service(submit_route);
 
+    // A separate scope, rather than adding `create_walk_session` to `api`
+    // above: `Idempotent` would also wrap `submit_route`, which already does
+    // its own idempotency-key handling through `RouteSubmissionService`.
+    let walk_sessions_api = web::scope("/api/v1")
+        .wrap(Idempotent::new(
+            idempotency_store,
+            MutationType::WalkSessions,
+        ))
+        .wrap(session_middleware(key.clone(), cookie_secure, same_site))
+        .service(create_walk_session);
+
+    let ws = web::scope("")
+        .wrap(session_middleware(key, cookie_secure, same_site))
+        .service(ws::ws_entry);
+
     let app = App::new()
         .app_data(health_state)
         .app_data(http_state)
         .app_data(ws_state)
         .wrap(Trace)
+        // CORS uses the compiled-in default allow-list; a `ws_origin_policy`
+        // set on `ServerConfig` applies to `/ws` upgrades only.
+        .wrap(SecurityHeaders::new(OriginPolicy::default_allow_list()))
         .service(api)
-        .service(ws::ws_entry)
+        .service(walk_sessions_api)
+        .service(ws)
         .service(ready)
         .service(live);
 
@@ -123,22 +160,29 @@ pub fn create_server(
     config: ServerConfig,
 ) -> std::io::Result<Server> {
     let server_health_state = health_state.clone();
-    let http_state = web::Data::new(HttpState::new(HttpStatePorts::new(
-        Arc::new(FixtureLoginService),
-        Arc::new(FixtureUsersQuery),
-        Arc::new(FixtureUserProfileQuery),
-        Arc::new(FixtureUserInterestsCommand),
-        Arc::new(FixtureRouteSubmissionService),
-    )));
-    let ws_state = web::Data::new(WsState::new(Arc::new(UserOnboardingService)));
+    let http_state: web::Data<HttpState> =
+        state_builders::build_http_state(&config, Arc::new(FixtureRouteSubmissionService));
+    let retry_worker = state_builders::build_route_annotations_retry_worker(&config);
+    let idempotency_store = state_builders::build_idempotency_store(&config);
     let ServerConfig {
         key,
         cookie_secure,
         same_site,
         bind_addr,
+        ws_compression,
+        ws_origin_policy,
         #[cfg(feature = "metrics")]
         prometheus,
+        ..
     } = config;
+    let ws_state = web::Data::new({
+        let mut state =
+            WsState::new(Arc::new(UserOnboardingService)).with_compression(ws_compression);
+        if let Some(origin_policy) = ws_origin_policy {
+            state = state.with_origin_policy(origin_policy);
+        }
+        state
+    });
 
     #[cfg(feature = "metrics")]
     let metrics_layer = MetricsLayer::from_option(prometheus);
@@ -148,6 +192,7 @@ pub fn create_server(
             health_state: server_health_state.clone(),
             http_state: http_state.clone(),
             ws_state: ws_state.clone(),
+            idempotency_store: idempotency_store.clone(),
             key: key.clone(),
             cookie_secure,
             same_site,
@@ -161,6 +206,87 @@ pub fn create_server(
     .bind(bind_addr)?
     .run();
 
+    spawn_drain_on_shutdown(ws_state);
+    if let Some((job_queue, worker)) = retry_worker {
+        spawn_annotation_retry_worker(job_queue, worker);
+    }
+
     health_state.mark_ready();
     Ok(server)
 }
+
+/// How long a worker waits before re-polling the annotation retry queue after
+/// finding it empty.
+const ANNOTATION_RETRY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often stale (crashed-worker) annotation retry leases are released
+/// back to `new` so another worker can pick them up.
+const ANNOTATION_RETRY_REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long a claimed annotation retry job may run before its lease is
+/// considered stale.
+const ANNOTATION_RETRY_LEASE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Drive the durable annotation-mutation retry queue for the life of the
+/// server.
+///
+/// Without this, [`RouteAnnotationsService`](backend::domain::RouteAnnotationsService)
+/// enqueues jobs when a keyed single mutation fails transiently, but nothing
+/// ever claims and replays them; the queue would grow write-only.
+fn spawn_annotation_retry_worker(
+    job_queue: Arc<dyn JobQueueRepository>,
+    worker: RouteAnnotationsRetryWorker,
+) {
+    actix_web::rt::spawn(async move {
+        let mut next_reap = tokio::time::Instant::now() + ANNOTATION_RETRY_REAP_INTERVAL;
+        loop {
+            if tokio::time::Instant::now() >= next_reap {
+                if let Err(error) =
+                    reap_stale_jobs(job_queue.as_ref(), ANNOTATION_RETRY_LEASE_TIMEOUT).await
+                {
+                    warn!(%error, "failed to reap stale annotation retry jobs");
+                }
+                next_reap = tokio::time::Instant::now() + ANNOTATION_RETRY_REAP_INTERVAL;
+            }
+
+            match worker.run_once().await {
+                Ok(true) => continue,
+                Ok(false) => tokio::time::sleep(ANNOTATION_RETRY_POLL_INTERVAL).await,
+                Err(error) => {
+                    warn!(%error, "annotation retry worker failed to process a job");
+                    tokio::time::sleep(ANNOTATION_RETRY_POLL_INTERVAL).await;
+                }
+            }
+        }
+    });
+}
+
+/// Drain open WebSocket connections once the process receives a shutdown signal.
+///
+/// Actix's own graceful shutdown stops accepting new connections and lets
+/// in-flight HTTP requests finish, but it has no notion of the long-lived
+/// WebSocket sessions tracked by [`ConnectionRegistry`](ws::registry::ConnectionRegistry);
+/// without this, a rolling deploy would drop open sockets instead of closing
+/// them with a reason the client can react to.
+fn spawn_drain_on_shutdown(ws_state: web::Data<WsState>) {
+    actix_web::rt::spawn(async move {
+        wait_for_shutdown_signal().await;
+        ws_state.registry().drain();
+    });
+}
+
+#[cfg(unix)]
+async fn wait_for_shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigterm = signal(SignalKind::terminate()).expect("install SIGTERM handler");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = sigterm.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
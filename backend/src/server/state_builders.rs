@@ -5,12 +5,14 @@ use std::sync::Arc;
 use actix_web::web;
 use async_trait::async_trait;
 
+use backend::domain::annotations::retry_worker::RouteAnnotationsRetryWorker;
 use backend::domain::ports::{
     CatalogueRepository, DescriptorRepository, FixtureCatalogueRepository,
-    FixtureDescriptorRepository, FixtureLoginService, FixtureOfflineBundleCommand,
-    FixtureOfflineBundleQuery, FixtureRouteAnnotationsCommand, FixtureRouteAnnotationsQuery,
-    FixtureUserInterestsCommand, FixtureUserPreferencesCommand, FixtureUserPreferencesQuery,
-    FixtureUserProfileQuery, FixtureUsersQuery, FixtureWalkSessionCommand, FixtureWalkSessionQuery,
+    FixtureDescriptorRepository, FixtureIdempotencyStore, FixtureLoginService,
+    FixtureOfflineBundleCommand, FixtureOfflineBundleQuery, FixtureRouteAnnotationsCommand,
+    FixtureRouteAnnotationsQuery, FixtureUserInterestsCommand, FixtureUserPreferencesCommand,
+    FixtureUserPreferencesQuery, FixtureUserProfileQuery, FixtureUsersQuery,
+    FixtureWalkSessionCommand, FixtureWalkSessionQuery, IdempotencyStore, JobQueueRepository,
     LoginService, OfflineBundleCommand, OfflineBundleQuery, RouteAnnotationsCommand,
     RouteAnnotationsQuery, RouteSubmissionService, UserPreferencesCommand, UserPreferencesQuery,
     UserRepository, UsersQuery, WalkSessionCommand, WalkSessionQuery,
@@ -21,11 +23,13 @@ use backend::domain::{
     WalkSessionQueryService,
 };
 use backend::inbound::http::state::{HttpState, HttpStateExtraPorts, HttpStatePorts};
+#[cfg(feature = "metrics")]
+use backend::outbound::metrics::PrometheusRouteAnnotationsTelemetry;
 use backend::outbound::persistence::DieselIdempotencyRepository;
 use backend::outbound::persistence::{
-    DbPool, DieselCatalogueRepository, DieselDescriptorRepository, DieselOfflineBundleRepository,
-    DieselRouteAnnotationRepository, DieselUserPreferencesRepository, DieselUserRepository,
-    DieselWalkSessionRepository,
+    DbPool, DieselCatalogueRepository, DieselDescriptorRepository, DieselIdempotencyStore,
+    DieselJobQueueRepository, DieselOfflineBundleRepository, DieselRouteAnnotationRepository,
+    DieselUserPreferencesRepository, DieselUserRepository, DieselWalkSessionRepository,
 };
 
 use super::ServerConfig;
@@ -244,15 +248,81 @@ build_idempotent_pair!(
     FixtureUserPreferencesQuery
 );
 
-build_idempotent_pair!(
-    build_route_annotations_pair,
-    dyn RouteAnnotationsCommand,
-    dyn RouteAnnotationsQuery,
-    DieselRouteAnnotationRepository::new,
-    RouteAnnotationsService::new,
-    FixtureRouteAnnotationsCommand,
-    FixtureRouteAnnotationsQuery
-);
+/// Build the route annotations command/query pair.
+///
+/// Unlike [`build_idempotent_pair!`], this is hand-written rather than
+/// macro-generated because the command side also takes a durable job queue,
+/// onto which keyed single mutations that fail with a transient error are
+/// enqueued for retry.
+fn build_route_annotations_pair(
+    config: &ServerConfig,
+) -> (Arc<dyn RouteAnnotationsCommand>, Arc<dyn RouteAnnotationsQuery>) {
+    match &config.db_pool {
+        Some(pool) => {
+            let repo = Arc::new(DieselRouteAnnotationRepository::new(pool.clone()));
+            let idempotency_repo = Arc::new(DieselIdempotencyRepository::new(pool.clone()));
+            let job_queue = Arc::new(DieselJobQueueRepository::new(pool.clone()));
+            let service = RouteAnnotationsService::new(repo, idempotency_repo)
+                .with_job_queue(job_queue);
+            #[cfg(feature = "metrics")]
+            let service = attach_route_annotations_telemetry(service, config);
+            let service = Arc::new(service);
+            (
+                service.clone() as Arc<dyn RouteAnnotationsCommand>,
+                service as Arc<dyn RouteAnnotationsQuery>,
+            )
+        }
+        None => (
+            Arc::new(FixtureRouteAnnotationsCommand),
+            Arc::new(FixtureRouteAnnotationsQuery),
+        ),
+    }
+}
+
+/// Attach the Prometheus-backed [`RouteAnnotationsTelemetry`] recorder when a
+/// registry is configured, leaving the service's default no-op recorder
+/// otherwise.
+///
+/// Metric registration only fails if the same metric is registered twice
+/// (e.g. a second call with the same registry); that's logged rather than
+/// propagated, since falling back to no-op telemetry is preferable to
+/// refusing to serve annotation requests.
+#[cfg(feature = "metrics")]
+fn attach_route_annotations_telemetry<R, I>(
+    service: RouteAnnotationsService<R, I>,
+    config: &ServerConfig,
+) -> RouteAnnotationsService<R, I> {
+    let Some(prometheus) = config.metrics() else {
+        return service;
+    };
+    match PrometheusRouteAnnotationsTelemetry::new(&prometheus.registry) {
+        Ok(telemetry) => service.with_telemetry(Arc::new(telemetry)),
+        Err(error) => {
+            tracing::warn!(%error, "failed to register route annotations telemetry metrics");
+            service
+        }
+    }
+}
+
+/// Build the durable retry worker that replays annotation mutations enqueued
+/// by [`RouteAnnotationsService`], plus the job queue handle the caller needs
+/// to reap stale leases.
+///
+/// Returns `None` when no database pool is configured, since there is no
+/// durable queue to poll with only fixtures in play.
+pub(super) fn build_route_annotations_retry_worker(
+    config: &ServerConfig,
+) -> Option<(Arc<dyn JobQueueRepository>, RouteAnnotationsRetryWorker)> {
+    let pool = config.db_pool.as_ref()?;
+    let job_queue = Arc::new(DieselJobQueueRepository::new(pool.clone()));
+    let (command, _query) = build_route_annotations_pair(config);
+    let worker = RouteAnnotationsRetryWorker::new(
+        job_queue.clone(),
+        command,
+        "route-annotations-retry-worker",
+    );
+    Some((job_queue, worker))
+}
 
 fn build_offline_bundles_pair(
     config: &ServerConfig,
@@ -295,6 +365,16 @@ fn build_walk_sessions_pair(
     }
 }
 
+/// Build the idempotency store backing the [`Idempotent`](backend::inbound::http::idempotency_middleware::Idempotent)
+/// middleware, selecting [`DieselIdempotencyStore`] when `config.db_pool` is
+/// present and falling back to [`FixtureIdempotencyStore`] otherwise.
+pub(super) fn build_idempotency_store(config: &ServerConfig) -> Arc<dyn IdempotencyStore> {
+    match &config.db_pool {
+        Some(pool) => Arc::new(DieselIdempotencyStore::new(pool.clone())),
+        None => Arc::new(FixtureIdempotencyStore),
+    }
+}
+
 /// Build the shared HTTP state from configured ports and fixture fallbacks.
 pub(super) fn build_http_state(
     config: &ServerConfig,
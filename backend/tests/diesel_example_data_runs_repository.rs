@@ -197,7 +197,13 @@ fn the_result_is_applied(world: SharedContext) {
 
 #[then("the result is already seeded")]
 fn the_result_is_already_seeded(world: SharedContext) {
-    assert_seeding_result(&world, SeedingResult::AlreadySeeded);
+    assert_seeding_result(
+        &world,
+        SeedingResult::AlreadySeeded {
+            recorded_user_count: TEST_USER_COUNT,
+            recorded_seed: TEST_SEED_VALUE,
+        },
+    );
 }
 
 #[then("is seeded returns true")]
@@ -284,11 +290,85 @@ fn is_seeded_returns_true_after_recording(diesel_world: Option<SharedContext>) {
     the_repository_records_a_seed(world.clone());
     the_result_is_applied(world.clone());
 
-    // Now seeded
+    // Still not seeded: the claim is only `pending` until finalized
+    the_repository_checks_if_seed_exists(world.clone());
+    is_seeded_returns_false(world.clone());
+
+    // Finalize, then seeded
+    finalize_seed(&world, TEST_SEED_KEY);
     the_repository_checks_if_seed_exists(world.clone());
     is_seeded_returns_true(world);
 }
 
+fn finalize_seed(world: &SharedContext, seed_key: &str) {
+    with_context_async(
+        world,
+        |_| seed_key,
+        |repo, key| async move { repo.finalize_seed(key).await },
+        |_ctx, result: Result<(), ExampleDataRunsError>| {
+            result.expect("finalize_seed succeeds");
+        },
+    );
+}
+
+/// Verify that a `pending` claim older than the configured timeout can be
+/// reclaimed, and that a fresh `pending` claim cannot.
+#[rstest]
+fn reclaim_abandoned_seed_resets_stale_pending_row(diesel_world: Option<SharedContext>) {
+    let Some(world) = diesel_world else {
+        eprintln!("SKIP-TEST-CLUSTER: reclaim_abandoned_seed_resets_stale_pending_row skipped");
+        return;
+    };
+
+    const RECLAIM_SEED_KEY: &str = "reclaim-seed";
+
+    a_diesel_backed_example_data_runs_repository(world.clone());
+
+    let ctx = world.lock().expect("context lock");
+    let repository = ctx.repository.clone();
+    let handle = ctx.runtime.handle().clone();
+    drop(ctx);
+
+    handle.block_on(async {
+        repository
+            .try_record_seed(RECLAIM_SEED_KEY, 1, 1)
+            .await
+            .expect("claim succeeds")
+    });
+
+    // A claim made moments ago is not yet stale under a long timeout.
+    let reclaimed_fresh = handle.block_on(async {
+        repository
+            .reclaim_abandoned_seed(RECLAIM_SEED_KEY, std::time::Duration::from_secs(3600))
+            .await
+            .expect("reclaim succeeds")
+    });
+    assert!(!reclaimed_fresh, "a fresh pending claim must not be reclaimed");
+
+    // The same claim is stale under a zero timeout.
+    let reclaimed_stale = handle.block_on(async {
+        repository
+            .reclaim_abandoned_seed(RECLAIM_SEED_KEY, std::time::Duration::from_secs(0))
+            .await
+            .expect("reclaim succeeds")
+    });
+    assert!(reclaimed_stale, "a pending claim past its timeout must be reclaimed");
+
+    // Reclaiming must delete the row outright, not just refresh it: a row
+    // left in place would keep conflicting with `ON CONFLICT (seed_key) DO
+    // NOTHING` forever, so the seed key would never become claimable again.
+    let reclaimed_result = handle.block_on(async {
+        repository
+            .try_record_seed(RECLAIM_SEED_KEY, 1, 1)
+            .await
+            .expect("re-claim succeeds after reclaim")
+    });
+    assert!(
+        matches!(reclaimed_result, SeedingResult::Applied),
+        "a reclaimed seed key must be claimable again, got {reclaimed_result:?}"
+    );
+}
+
 /// Verify that concurrent calls to `try_record_seed` for the same seed key
 /// still respect once-only semantics: exactly one caller applies the seed,
 /// the rest observe it as already seeded.
@@ -321,7 +401,7 @@ fn concurrent_calls_for_same_seed_are_once_only(diesel_world: Option<SharedConte
         .count();
     let already_seeded_count = results
         .iter()
-        .filter(|r| matches!(r, Ok(SeedingResult::AlreadySeeded)))
+        .filter(|r| matches!(r, Ok(SeedingResult::AlreadySeeded { .. })))
         .count();
     let error_count = results.iter().filter(|r| r.is_err()).count();
 
@@ -341,7 +421,8 @@ fn concurrent_calls_for_same_seed_are_once_only(diesel_world: Option<SharedConte
 }
 
 /// Verify that recording the same seed_key with different metadata values
-/// still returns AlreadySeeded and does not update the original record.
+/// still returns `AlreadySeeded`, does not update the original record, and
+/// surfaces the historical row's metadata so callers can detect drift.
 #[rstest]
 fn same_seed_key_with_different_metadata_returns_already_seeded(
     diesel_world: Option<SharedContext>,
@@ -376,6 +457,7 @@ fn same_seed_key_with_different_metadata_returns_already_seeded(
     );
 
     // Second insert with different metadata - should return AlreadySeeded
+    // with the original (not the new) metadata, so drift is detectable.
     let second_result = handle.block_on(async {
         repository
             .try_record_seed(
@@ -385,8 +467,12 @@ fn same_seed_key_with_different_metadata_returns_already_seeded(
             )
             .await
     });
-    assert!(
-        matches!(second_result, Ok(SeedingResult::AlreadySeeded)),
-        "second insert with different metadata should return AlreadySeeded, got: {second_result:?}"
+    assert_eq!(
+        second_result,
+        Ok(SeedingResult::AlreadySeeded {
+            recorded_user_count: ORIGINAL_USER_COUNT,
+            recorded_seed: ORIGINAL_SEED_VALUE,
+        }),
+        "second insert with different metadata should surface the original recorded metadata"
     );
 }
@@ -0,0 +1,115 @@
+//! Integration tests for `DieselJobQueueRepository` against embedded PostgreSQL.
+//!
+//! `claim` relies on `SELECT ... FOR UPDATE SKIP LOCKED` to let concurrent
+//! workers race for rows without double-assigning one; that guarantee only
+//! means anything when it is exercised by genuinely concurrent callers
+//! against a real database, so this suite spawns several workers on a
+//! multi-threaded runtime rather than asserting on the SQL text alone.
+
+use backend::domain::ports::JobQueueRepository;
+use backend::outbound::persistence::{DbPool, DieselJobQueueRepository, PoolConfig};
+use pg_embedded_setup_unpriv::TemporaryDatabase;
+use rstest::{fixture, rstest};
+use serde_json::json;
+use tokio::runtime::Runtime;
+use tokio::task::JoinSet;
+
+mod support;
+
+use support::atexit_cleanup::shared_cluster_handle;
+use support::{handle_cluster_setup_failure, provision_template_database};
+
+const QUEUE: &str = "route-annotations-retry";
+const JOB_COUNT: usize = 20;
+const WORKER_COUNT: usize = 5;
+
+struct TestContext {
+    runtime: Runtime,
+    repository: DieselJobQueueRepository,
+    _database: TemporaryDatabase,
+}
+
+fn setup_context() -> Result<TestContext, String> {
+    let runtime = Runtime::new().map_err(|err| err.to_string())?;
+    let cluster = shared_cluster_handle().map_err(|err| err.to_string())?;
+    let temp_db = provision_template_database(cluster).map_err(|err| err.to_string())?;
+    let database_url = temp_db.url().to_string();
+
+    let config = PoolConfig::new(database_url.as_str())
+        .with_max_size(WORKER_COUNT as u32 + 1)
+        .with_min_idle(Some(1));
+    let pool = runtime
+        .block_on(async { DbPool::new(config).await })
+        .map_err(|err| err.to_string())?;
+
+    let repository = DieselJobQueueRepository::new(pool);
+
+    Ok(TestContext {
+        runtime,
+        repository,
+        _database: temp_db,
+    })
+}
+
+#[fixture]
+fn repo_context() -> Option<TestContext> {
+    match setup_context() {
+        Ok(ctx) => Some(ctx),
+        Err(reason) => handle_cluster_setup_failure(reason),
+    }
+}
+
+#[rstest]
+fn job_queue_concurrent_claims_never_double_assign(repo_context: Option<TestContext>) {
+    let Some(context) = repo_context else {
+        eprintln!("SKIP-TEST-CLUSTER: job_queue_concurrent_claims_never_double_assign skipped");
+        return;
+    };
+
+    let repository = context.repository.clone();
+
+    context.runtime.block_on(async {
+        for seq in 0..JOB_COUNT {
+            repository
+                .enqueue(QUEUE, json!({ "seq": seq }))
+                .await
+                .expect("enqueue job");
+        }
+
+        let mut workers = JoinSet::new();
+        for worker_index in 0..WORKER_COUNT {
+            let repository = repository.clone();
+            workers.spawn(async move {
+                let worker_id = format!("worker-{worker_index}");
+                let mut claimed = Vec::new();
+                while let Some(job) = repository
+                    .claim(QUEUE, &worker_id)
+                    .await
+                    .expect("claim succeeds")
+                {
+                    claimed.push(job.id);
+                }
+                claimed
+            });
+        }
+
+        let mut claimed_ids = Vec::new();
+        while let Some(result) = workers.join_next().await {
+            claimed_ids.extend(result.expect("worker task panicked"));
+        }
+
+        let claimed_count = claimed_ids.len();
+        claimed_ids.sort_unstable();
+        claimed_ids.dedup();
+
+        assert_eq!(
+            claimed_ids.len(),
+            claimed_count,
+            "no job should be claimed by more than one worker"
+        );
+        assert_eq!(
+            claimed_count, JOB_COUNT,
+            "every enqueued job should be claimed exactly once"
+        );
+    });
+}
@@ -7,7 +7,9 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
 
-use backend::domain::ports::{RouteAnnotationRepository, RouteAnnotationRepositoryError};
+use backend::domain::ports::{
+    AnnotationBatchWrite, RouteAnnotationRepository, RouteAnnotationRepositoryError,
+};
 use backend::domain::{RouteNote, RouteNoteContent, RouteProgress, UserId};
 use backend::outbound::persistence::{DbPool, DieselRouteAnnotationRepository, PoolConfig};
 use pg_embedded_setup_unpriv::TemporaryDatabase;
@@ -346,3 +348,65 @@ fn route_progress_rejects_revision_mismatch(repo_context: Option<TestContext>) {
         },
     );
 }
+
+#[rstest]
+fn apply_batch_rolls_back_all_writes_when_one_fails(repo_context: Option<TestContext>) {
+    let Some(context) = repo_context else {
+        eprintln!("SKIP-TEST-CLUSTER: apply_batch_rolls_back_all_writes_when_one_fails skipped");
+        return;
+    };
+
+    let repository = context.repository.clone();
+    let note_id = Uuid::new_v4();
+    let note = RouteNote::new(
+        note_id,
+        context.route_id,
+        context.user_id.clone(),
+        RouteNoteContent::new("Batched note"),
+    );
+    // Progress already stored at revision 2, so the batch's update below,
+    // which expects revision 1, fails the commit-time revision recheck.
+    let existing_progress = RouteProgress::builder(context.route_id, context.user_id.clone())
+        .visited_stop_ids(vec![Uuid::new_v4()])
+        .revision(2)
+        .build();
+    context
+        .runtime
+        .block_on(async { repository.save_progress(&existing_progress, None).await })
+        .expect("seed existing progress");
+    let progress = RouteProgress::builder(context.route_id, context.user_id.clone())
+        .visited_stop_ids(vec![Uuid::new_v4()])
+        .revision(2)
+        .build();
+
+    let writes = vec![
+        AnnotationBatchWrite::UpsertNote {
+            note,
+            expected_revision: None,
+        },
+        AnnotationBatchWrite::UpdateProgress {
+            progress,
+            expected_revision: Some(1),
+        },
+    ];
+
+    let error = context
+        .runtime
+        .block_on(async { repository.apply_batch(&writes).await })
+        .expect_err("batch with a failing write is rejected");
+
+    assert_eq!(error.0, 1);
+    assert!(matches!(
+        error.1,
+        RouteAnnotationRepositoryError::RevisionMismatch { expected: 1, .. }
+    ));
+
+    let persisted_note = context
+        .runtime
+        .block_on(async { repository.find_note_by_id(&note_id).await })
+        .expect("fetch note");
+    assert!(
+        persisted_note.is_none(),
+        "the note write must not survive a rolled-back batch"
+    );
+}
@@ -198,11 +198,13 @@ fn the_result_is(world: &ExampleDataRunsWorld, expected: String) {
     match expected.as_str() {
         "\"applied\"" => match result {
             Ok(SeedingResult::Applied) => {}
-            Ok(SeedingResult::AlreadySeeded) => panic!("expected Applied, got AlreadySeeded"),
+            Ok(SeedingResult::AlreadySeeded { .. }) => {
+                panic!("expected Applied, got AlreadySeeded")
+            }
             Err(err) => panic!("expected Applied, got error: {err}"),
         },
         "\"already seeded\"" => match result {
-            Ok(SeedingResult::AlreadySeeded) => {}
+            Ok(SeedingResult::AlreadySeeded { .. }) => {}
             Ok(SeedingResult::Applied) => panic!("expected AlreadySeeded, got Applied"),
             Err(err) => panic!("expected AlreadySeeded, got error: {err}"),
         },
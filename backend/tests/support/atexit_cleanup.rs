@@ -10,6 +10,16 @@
 //! This module registers a `libc::atexit` handler that reads `postmaster.pid`,
 //! sends `SIGTERM`, and waits for graceful shutdown, bridging the gap until the
 //! library provides built-in process-exit shutdown.
+//!
+//! A nextest run spawns hundreds of binaries, each calling
+//! [`shared_cluster_handle()`]; naively stopping PostgreSQL on every exit would
+//! force a fresh bootstrap for each one. Instead, each binary registers its
+//! PID in a reference-count file sitting next to [`SHARED_CLUSTER_LOCK_FILE`]
+//! on success, and removes it again on exit; only the binary that decrements
+//! the count to zero actually stops the cluster. Reads and writes of that
+//! file happen while this process holds the `flock(LOCK_EX)` acquired in
+//! [`acquire_shared_cluster_process_lock`], and are pruned of stale PIDs left
+//! by binaries that crashed before decrementing.
 
 #[cfg(unix)]
 use std::ffi::CString;
@@ -33,6 +43,10 @@ const SHARED_CLUSTER_RETRIES: usize = 5;
 const SHARED_CLUSTER_RETRY_DELAY: Duration = Duration::from_millis(500);
 #[cfg(unix)]
 const SHARED_CLUSTER_LOCK_FILE: &str = "wildside-pg-embedded-shared-cluster.lock";
+/// Sibling of [`SHARED_CLUSTER_LOCK_FILE`] recording the PIDs of binaries
+/// currently using the shared cluster, one per line.
+#[cfg(unix)]
+const SHARED_CLUSTER_REFCOUNT_FILE: &str = "wildside-pg-embedded-shared-cluster.refcount";
 
 /// Postmaster PID captured at registration time.
 #[cfg(unix)]
@@ -41,15 +55,17 @@ static PG_POSTMASTER_PID: AtomicI32 = AtomicI32::new(0);
 /// Data directory for re-reading `postmaster.pid` at exit time.
 #[cfg(unix)]
 static PG_DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
-#[cfg(unix)]
-static SHARED_CLUSTER_PROCESS_LOCK_FD: OnceLock<i32> = OnceLock::new();
 
+/// Acquires the cross-process shared cluster lock and returns its file
+/// descriptor.
+///
+/// The lock serializes the bootstrap-and-register critical section, and on
+/// exit the whole decrement-then-kill sequence in [`stop_postgres_on_exit`];
+/// callers must release it with [`release_shared_cluster_process_lock`] as
+/// soon as that section completes, so other nextest binaries can make
+/// progress concurrently.
 #[cfg(unix)]
-fn acquire_shared_cluster_process_lock() -> BootstrapResult<()> {
-    if SHARED_CLUSTER_PROCESS_LOCK_FD.get().is_some() {
-        return Ok(());
-    }
-
+fn acquire_shared_cluster_process_lock() -> BootstrapResult<i32> {
     let lock_path = std::env::temp_dir().join(SHARED_CLUSTER_LOCK_FILE);
     let lock_path_bytes = lock_path.as_os_str().as_bytes();
     let lock_path_cstring = CString::new(lock_path_bytes).map_err(|error| {
@@ -89,13 +105,90 @@ fn acquire_shared_cluster_process_lock() -> BootstrapResult<()> {
         )));
     }
 
-    if SHARED_CLUSTER_PROCESS_LOCK_FD.set(fd).is_err() {
-        // SAFETY: `fd` is valid and must be closed when another caller won `set`.
-        unsafe {
-            libc::close(fd);
-        }
+    Ok(fd)
+}
+
+/// Releases a lock previously acquired by
+/// [`acquire_shared_cluster_process_lock`], letting other binaries blocked on
+/// `flock(LOCK_EX)` proceed.
+#[cfg(unix)]
+fn release_shared_cluster_process_lock(fd: i32) {
+    // SAFETY: `fd` is a valid, locked descriptor returned by
+    // `acquire_shared_cluster_process_lock`.
+    unsafe {
+        libc::flock(fd, libc::LOCK_UN);
+        libc::close(fd);
     }
-    Ok(())
+}
+
+#[cfg(unix)]
+fn shared_cluster_refcount_path() -> PathBuf {
+    std::env::temp_dir().join(SHARED_CLUSTER_REFCOUNT_FILE)
+}
+
+/// Reads the PIDs recorded in the reference-count file, dropping any that no
+/// longer correspond to a live process. Stale entries are left behind by
+/// binaries that crashed before decrementing on exit.
+///
+/// Must be called while holding the shared cluster process lock.
+#[cfg(unix)]
+fn read_live_refcount_pids(path: &std::path::Path) -> Vec<i32> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| line.trim().parse::<i32>().ok())
+        // SAFETY: signal 0 only probes liveness; it sends nothing.
+        .filter(|&pid| unsafe { libc::kill(pid, 0) } == 0)
+        .collect()
+}
+
+#[cfg(unix)]
+fn write_refcount_pids(path: &std::path::Path, pids: &[i32]) -> std::io::Result<()> {
+    let content = pids.iter().map(i32::to_string).collect::<Vec<_>>().join("\n");
+    std::fs::write(path, content)
+}
+
+/// Registers `pid` as a user of the shared cluster, pruning stale entries
+/// left by crashed binaries in the process.
+///
+/// Must be called while holding the shared cluster process lock.
+#[cfg(unix)]
+fn increment_shared_cluster_refcount(pid: i32) {
+    increment_shared_cluster_refcount_at(&shared_cluster_refcount_path(), pid);
+}
+
+#[cfg(unix)]
+fn increment_shared_cluster_refcount_at(path: &std::path::Path, pid: i32) {
+    let mut pids = read_live_refcount_pids(path);
+    if !pids.contains(&pid) {
+        pids.push(pid);
+    }
+    if let Err(error) = write_refcount_pids(path, &pids) {
+        eprintln!("pg-embed: failed to record shared cluster reference for PID {pid}: {error}");
+    }
+}
+
+/// Unregisters `pid` as a user of the shared cluster.
+///
+/// Returns `true` when no other live binaries remain registered, meaning the
+/// caller is responsible for stopping PostgreSQL.
+///
+/// Must be called while holding the shared cluster process lock.
+#[cfg(unix)]
+fn decrement_shared_cluster_refcount(pid: i32) -> bool {
+    decrement_shared_cluster_refcount_at(&shared_cluster_refcount_path(), pid)
+}
+
+#[cfg(unix)]
+fn decrement_shared_cluster_refcount_at(path: &std::path::Path, pid: i32) -> bool {
+    let mut pids = read_live_refcount_pids(path);
+    pids.retain(|&candidate| candidate != pid);
+    if let Err(error) = write_refcount_pids(path, &pids) {
+        eprintln!("pg-embed: failed to release shared cluster reference for PID {pid}: {error}");
+    }
+    pids.is_empty()
 }
 
 /// Returns the shared cluster handle and registers an atexit handler to stop
@@ -117,24 +210,30 @@ fn acquire_shared_cluster_process_lock() -> BootstrapResult<()> {
 pub fn shared_cluster_handle() -> BootstrapResult<&'static ClusterHandle> {
     ensure_stable_password();
     #[cfg(unix)]
-    acquire_shared_cluster_process_lock()?;
+    let process_lock_fd = acquire_shared_cluster_process_lock()?;
+
     let mut attempt = 1;
-    loop {
+    let result = loop {
         match pg_embedded_setup_unpriv::test_support::shared_cluster_handle() {
             Ok(handle) => {
                 #[cfg(unix)]
                 register_process_exit_cleanup(handle);
-                return Ok(handle);
+                break Ok(handle);
             }
             Err(error) => {
                 if attempt >= SHARED_CLUSTER_RETRIES {
-                    return Err(error);
+                    break Err(error);
                 }
                 std::thread::sleep(SHARED_CLUSTER_RETRY_DELAY);
                 attempt += 1;
             }
         }
-    }
+    };
+
+    #[cfg(unix)]
+    release_shared_cluster_process_lock(process_lock_fd);
+
+    result
 }
 
 /// Ensures `PG_PASSWORD` is set to a stable value so the password remains
@@ -164,12 +263,13 @@ fn read_postmaster_pid(data_dir: &std::path::Path) -> Option<i32> {
     content.lines().next()?.trim().parse().ok()
 }
 
-/// Sends SIGTERM to the PostgreSQL postmaster and waits for shutdown.
+/// Decrements this binary's shared-cluster reference and, if it was the last
+/// one standing, sends SIGTERM to the PostgreSQL postmaster and waits for
+/// shutdown.
 ///
-/// Registered via `libc::atexit` so the shared cluster is stopped when the
-/// test binary exits. Re-reads `postmaster.pid` at exit time and only signals
-/// when the on-disk PID still matches the stored value, guarding against PID
-/// reuse.
+/// Registered via `libc::atexit`. Re-reads `postmaster.pid` at exit time and
+/// only signals when the on-disk PID still matches the stored value, guarding
+/// against PID reuse.
 #[cfg(unix)]
 extern "C" fn stop_postgres_on_exit() {
     let stored_pid = PG_POSTMASTER_PID.load(Ordering::Relaxed);
@@ -177,6 +277,35 @@ extern "C" fn stop_postgres_on_exit() {
         return;
     }
 
+    let pid = std::process::id() as i32;
+    // Hold the process lock across the whole decrement-then-kill sequence,
+    // not just the decrement: releasing it beforehand would let another
+    // binary increment the refcount (believing the cluster is still up)
+    // between our "I'm the last one" check and the SIGTERM that tears it
+    // down.
+    let fd = match acquire_shared_cluster_process_lock() {
+        Ok(fd) => fd,
+        Err(error) => {
+            eprintln!(
+                "pg-embed: failed to acquire shared cluster lock during exit cleanup: {error}"
+            );
+            return;
+        }
+    };
+
+    if decrement_shared_cluster_refcount(pid) {
+        stop_postgres_locked(stored_pid);
+    }
+
+    release_shared_cluster_process_lock(fd);
+}
+
+/// Sends `SIGTERM` to the postmaster and waits for it to exit, force-killing
+/// it if it doesn't within the grace period. Must be called while holding
+/// the shared cluster process lock, so no other binary can register itself
+/// as a new user of the cluster while it is being torn down.
+#[cfg(unix)]
+fn stop_postgres_locked(stored_pid: i32) {
     // Re-read postmaster.pid to guard against PID reuse.
     let pid = match PG_DATA_DIR.get().and_then(|dir| read_postmaster_pid(dir)) {
         Some(current_pid) if current_pid == stored_pid => current_pid,
@@ -206,9 +335,10 @@ extern "C" fn stop_postgres_on_exit() {
     }
 }
 
-/// Records the postmaster PID and registers an `atexit` handler so the
-/// shared cluster is stopped when the test binary exits. Uses
-/// `compare_exchange` to ensure the handler is registered at most once.
+/// Records the postmaster PID, registers this binary as a user of the shared
+/// cluster, and registers an `atexit` handler so the cluster is stopped once
+/// the last registered binary exits. Uses `compare_exchange` to ensure the
+/// handler is registered at most once per process.
 #[cfg(unix)]
 fn register_process_exit_cleanup(handle: &ClusterHandle) {
     let data_dir = &handle.settings().data_dir;
@@ -225,6 +355,7 @@ fn register_process_exit_cleanup(handle: &ClusterHandle) {
         return;
     }
 
+    increment_shared_cluster_refcount(std::process::id() as i32);
     let _ = PG_DATA_DIR.set(data_dir.clone());
 
     // SAFETY: `stop_postgres_on_exit` is a valid `extern "C"` function with
@@ -278,6 +409,58 @@ mod tests {
         assert_eq!(super::read_postmaster_pid(dir.path()), None);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn refcount_round_trips_through_the_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("refcount");
+        let pid = std::process::id() as i32;
+
+        super::increment_shared_cluster_refcount_at(&path, pid);
+        assert_eq!(super::read_live_refcount_pids(&path), vec![pid]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refcount_prunes_stale_pids_on_read() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("refcount");
+        let live_pid = std::process::id() as i32;
+        // Unlikely to be a live PID; liveness sweeps should drop it silently.
+        let stale_pid = 999_999;
+
+        super::write_refcount_pids(&path, &[stale_pid, live_pid]).expect("write");
+        assert_eq!(super::read_live_refcount_pids(&path), vec![live_pid]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn decrement_reports_whether_any_binaries_remain() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("refcount");
+        let pid = std::process::id() as i32;
+        // The parent (test harness) process is guaranteed to be alive too.
+        // SAFETY: `getppid` has no preconditions.
+        let other_live_pid = unsafe { libc::getppid() } as i32;
+
+        super::write_refcount_pids(&path, &[pid]).expect("write");
+        assert!(super::decrement_shared_cluster_refcount_at(&path, pid));
+
+        super::write_refcount_pids(&path, &[pid, other_live_pid]).expect("write");
+        assert!(!super::decrement_shared_cluster_refcount_at(&path, pid));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn process_lock_can_be_reacquired_after_release() {
+        let fd = super::acquire_shared_cluster_process_lock().expect("first acquire");
+        super::release_shared_cluster_process_lock(fd);
+
+        // If release failed to actually unlock, this would block forever.
+        let fd = super::acquire_shared_cluster_process_lock().expect("second acquire");
+        super::release_shared_cluster_process_lock(fd);
+    }
+
     #[test]
     fn ensure_stable_password_does_not_overwrite_existing_value() {
         let _guard = env_lock::lock_env([("PG_PASSWORD", Some("custom_value"))]);
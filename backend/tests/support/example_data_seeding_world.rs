@@ -270,10 +270,16 @@ fn assert_seeding_result(
     }
 }
 
+/// `set_registry` always writes a fixture registry with `seed: 42,
+/// userCount: 2`, so a historical run recorded by an earlier seeding
+/// attempt in the same scenario always carries those exact values.
 fn parse_expected_result(expected: &str) -> SeedingResult {
     match expected {
         "applied" => SeedingResult::Applied,
-        "already seeded" => SeedingResult::AlreadySeeded,
+        "already seeded" => SeedingResult::AlreadySeeded {
+            recorded_user_count: 2,
+            recorded_seed: 42,
+        },
         other => panic!("unknown expected result: {other}"),
     }
 }